@@ -1,17 +1,131 @@
 //! Module to import .atlas files
 
+use std::collections::HashMap;
 use std::io::{BufReader, Lines};
 use std::io::prelude::*;
 use std::fmt;
 use std::error::Error;
-use std::str::ParseBoolError;
+use std::str::{FromStr, ParseBoolError};
+
+/// pixel format of a page image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// alpha only
+    Alpha,
+    /// intensity only
+    Intensity,
+    /// luminance and alpha
+    LuminanceAlpha,
+    /// 16 bits rgb
+    RGB565,
+    /// 16 bits rgba
+    RGBA4444,
+    /// 24 bits rgb
+    RGB888,
+    /// 32 bits rgba
+    RGBA8888
+}
+
+impl FromStr for Format {
+    type Err = AtlasError;
+    fn from_str(s: &str) -> Result<Format, AtlasError> {
+        match s {
+            "Alpha" => Ok(Format::Alpha),
+            "Intensity" => Ok(Format::Intensity),
+            "LuminanceAlpha" => Ok(Format::LuminanceAlpha),
+            "RGB565" => Ok(Format::RGB565),
+            "RGBA4444" => Ok(Format::RGBA4444),
+            "RGB888" => Ok(Format::RGB888),
+            "RGBA8888" => Ok(Format::RGBA8888),
+            _ => Err(AtlasError::Unexpected("unknown format"))
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// minification or magnification filter of a page image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextureFilter {
+    /// nearest texel
+    Nearest,
+    /// linear interpolation of texels
+    Linear,
+    /// mipmaps, chosen by the renderer
+    MipMap,
+    /// nearest texel of the nearest mipmap
+    MipMapNearestNearest,
+    /// linear interpolation in the nearest mipmap
+    MipMapLinearNearest,
+    /// nearest texels of the two nearest mipmaps, interpolated
+    MipMapNearestLinear,
+    /// linear interpolation in the two nearest mipmaps (trilinear)
+    MipMapLinearLinear
+}
+
+impl FromStr for TextureFilter {
+    type Err = AtlasError;
+    fn from_str(s: &str) -> Result<TextureFilter, AtlasError> {
+        match s {
+            "Nearest" => Ok(TextureFilter::Nearest),
+            "Linear" => Ok(TextureFilter::Linear),
+            "MipMap" => Ok(TextureFilter::MipMap),
+            "MipMapNearestNearest" => Ok(TextureFilter::MipMapNearestNearest),
+            "MipMapLinearNearest" => Ok(TextureFilter::MipMapLinearNearest),
+            "MipMapNearestLinear" => Ok(TextureFilter::MipMapNearestLinear),
+            "MipMapLinearLinear" => Ok(TextureFilter::MipMapLinearLinear),
+            _ => Err(AtlasError::Unexpected("unknown filter"))
+        }
+    }
+}
+
+impl fmt::Display for TextureFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// wrapping of the texture coordinates of a page image along an axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextureWrap {
+    /// coordinates are clamped
+    ClampToEdge,
+    /// the image is repeated
+    Repeat
+}
+
+impl FromStr for TextureWrap {
+    type Err = AtlasError;
+    fn from_str(s: &str) -> Result<TextureWrap, AtlasError> {
+        match s {
+            "ClampToEdge" => Ok(TextureWrap::ClampToEdge),
+            "Repeat" => Ok(TextureWrap::Repeat),
+            _ => Err(AtlasError::Unexpected("unknown wrap"))
+        }
+    }
+}
+
+/// rotation of a region packed in its page
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rotation {
+    /// not rotated
+    None,
+    /// rotated 90 degrees clockwise, `rotate: true` in legacy atlases
+    CW90,
+    /// rotated 270 degrees clockwise
+    CW270
+}
 
 /// atlas texture
 pub struct Texture {
     /// name
     pub name: String,
     /// rotate
-    pub rotate: bool,
+    pub rotate: Rotation,
     /// position
     pub xy: (u16, u16),
     /// size
@@ -22,112 +136,631 @@ pub struct Texture {
     pub offset: (u16, u16),
     /// index
     pub index: i16,
+    /// nine-patch splits, in pixels from the left, right, top and bottom edges
+    pub split: Option<[u16; 4]>,
+    /// nine-patch content padding, in pixels from the left, right, top and bottom edges
+    pub pad: Option<[u16; 4]>,
 }
 
-/// Iterator to parse attachments from a common image
-pub struct Atlas<R: Read> {
+impl Texture {
+
+    /// gets the normalized texture coordinates of the region in a page of `page_size` pixels
+    ///
+    /// Corners are ordered as `Sprite::positions`: top-left, top-right, bottom-right,
+    /// bottom-left. Rotated regions are stored rotated clockwise in the page, see `Rotation`.
+    ///
+    /// The coordinates cover the packed region only: when whitespace has been trimmed, the quad
+    /// must be shrunk to the part of the original image given by `trim`.
+    pub fn uvs(&self, page_size: (u16, u16)) -> [[f32; 2]; 4] {
+        let (width, height) = (page_size.0 as f32, page_size.1 as f32);
+        let (u, v) = (self.xy.0 as f32 / width, self.xy.1 as f32 / height);
+        match self.rotate {
+            Rotation::None => {
+                let (u2, v2) = (u + self.size.0 as f32 / width, v + self.size.1 as f32 / height);
+                [[u, v], [u2, v], [u2, v2], [u, v2]]
+            },
+            Rotation::CW90 => {
+                let (u2, v2) = (u + self.size.1 as f32 / width, v + self.size.0 as f32 / height);
+                [[u, v2], [u, v], [u2, v], [u2, v2]]
+            },
+            Rotation::CW270 => {
+                let (u2, v2) = (u + self.size.1 as f32 / width, v + self.size.0 as f32 / height);
+                [[u2, v], [u2, v2], [u, v2], [u, v]]
+            }
+        }
+    }
+
+    /// gets the part of the original image covered by the packed region, as normalized
+    /// `[left, bottom]` and `[right, top]` corners
+    ///
+    /// It is `[[0, 0], [1, 1]]` unless whitespace has been trimmed when packing the atlas.
+    pub fn trim(&self) -> [[f32; 2]; 2] {
+        if self.orig.0 == 0 || self.orig.1 == 0 {
+            return [[0.0, 0.0], [1.0, 1.0]];
+        }
+        let (width, height) = (self.orig.0 as f32, self.orig.1 as f32);
+        let (left, bottom) = (self.offset.0 as f32 / width, self.offset.1 as f32 / height);
+        [[left, bottom], [left + self.size.0 as f32 / width, bottom + self.size.1 as f32 / height]]
+    }
+}
+
+/// Shrinks a quad to the part of a trimmed region given by `Texture::trim`
+///
+/// Corners are ordered as `Sprite::positions`: top-left, top-right, bottom-right, bottom-left.
+pub fn trim_quad(quad: [[f32; 2]; 4], trim: [[f32; 2]; 2]) -> [[f32; 2]; 4] {
+    let (tl, br, bl) = (quad[0], quad[2], quad[3]);
+    let point = |x: f32, y: f32| [bl[0] + x * (br[0] - bl[0]) + y * (tl[0] - bl[0]),
+                                  bl[1] + x * (br[1] - bl[1]) + y * (tl[1] - bl[1])];
+    let (left, bottom, right, top) = (trim[0][0], trim[0][1], trim[1][0], trim[1][1]);
+    [point(left, top), point(right, top), point(right, bottom), point(left, bottom)]
+}
+
+/// Page of an atlas: an image and the regions packed in it
+pub struct AtlasPage {
     /// file
     pub file: String,
+    /// width and height in pixels, if given by the atlas (see `Texture::uvs`)
+    pub size: Option<(u16, u16)>,
     /// format
-    pub format: String,
-    /// filter
-    pub filter: String,
-    /// repeat
-    pub repeat: String,
-    lines: Lines<BufReader<R>>
+    pub format: Format,
+    /// minification and magnification filters
+    pub filter: (TextureFilter, TextureFilter),
+    /// wrapping along x and y
+    pub repeat: (TextureWrap, TextureWrap),
+    /// whether the image has premultiplied alpha
+    pub pma: bool,
+    /// scale the regions have been packed at
+    pub scale: f32,
+    /// regions of the page
+    pub textures: Vec<Texture>
+}
+
+impl AtlasPage {
+
+    /// Gets the textures of the page by name
+    ///
+    /// Indexed regions (e.g. frames of a sequence) share their name, they are sorted by index.
+    pub fn regions(&self) -> HashMap<&str, Vec<&Texture>> {
+        let mut regions = HashMap::new();
+        for texture in &self.textures {
+            regions.entry(&*texture.name).or_insert(Vec::new()).push(texture);
+        }
+        for textures in regions.values_mut() {
+            textures.sort_by_key(|t| t.index);
+        }
+        regions
+    }
+
+    /// Writes the page in the legacy .atlas layout, preceded by the blank line separating pages
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> ::std::io::Result<()> {
+        try!(write!(writer, "\n{}\n", self.file));
+        if let Some((width, height)) = self.size {
+            try!(write!(writer, "size: {},{}\n", width, height));
+        }
+        let repeat = match self.repeat {
+            (TextureWrap::ClampToEdge, TextureWrap::ClampToEdge) => "none",
+            (TextureWrap::Repeat, TextureWrap::ClampToEdge) => "x",
+            (TextureWrap::ClampToEdge, TextureWrap::Repeat) => "y",
+            (TextureWrap::Repeat, TextureWrap::Repeat) => "xy"
+        };
+        try!(write!(writer, "format: {}\nfilter: {},{}\nrepeat: {}\n", self.format, self.filter.0, self.filter.1,
+                    repeat));
+        if self.pma {
+            try!(write!(writer, "pma: true\n"));
+        }
+        if self.scale != 1.0 {
+            try!(write!(writer, "scale: {}\n", self.scale));
+        }
+
+        for texture in &self.textures {
+            let rotate = match texture.rotate {
+                Rotation::None => "false",
+                Rotation::CW90 => "true",
+                Rotation::CW270 => "270"
+            };
+            try!(write!(writer, "{}\n  rotate: {}\n  xy: {}, {}\n  size: {}, {}\n", texture.name, rotate,
+                        texture.xy.0, texture.xy.1, texture.size.0, texture.size.1));
+            if let Some(split) = texture.split {
+                try!(write!(writer, "  split: {}, {}, {}, {}\n", split[0], split[1], split[2], split[3]));
+            }
+            if let Some(pad) = texture.pad {
+                try!(write!(writer, "  pad: {}, {}, {}, {}\n", pad[0], pad[1], pad[2], pad[3]));
+            }
+            try!(write!(writer, "  orig: {}, {}\n  offset: {}, {}\n  index: {}\n", texture.orig.0,
+                        texture.orig.1, texture.offset.0, texture.offset.1, texture.index));
+        }
+        Ok(())
+    }
+}
+
+/// Header of a page, before its regions
+struct PageHeader {
+    file: String,
+    size: Option<(u16, u16)>,
+    format: Format,
+    filter: (TextureFilter, TextureFilter),
+    repeat: (TextureWrap, TextureWrap),
+    pma: bool,
+    scale: f32
+}
+
+impl PageHeader {
+
+    /// parses the `key: value` fields of a page, missing ones take their default value
+    fn parse(file: String, values: Vec<(String, String)>) -> Result<PageHeader, AtlasError> {
+        let mut header = PageHeader {
+            file: file,
+            size: None,
+            format: Format::RGBA8888,
+            filter: (TextureFilter::Nearest, TextureFilter::Nearest),
+            repeat: (TextureWrap::ClampToEdge, TextureWrap::ClampToEdge),
+            pma: false,
+            scale: 1f32
+        };
+        for (key, value) in values {
+            match &*key {
+                "size" => {
+                    let size = try!(parse_u16s(&value, 2));
+                    header.size = Some((size[0], size[1]));
+                },
+                "format" => header.format = try!(value.parse()),
+                "filter" => {
+                    let mut filters = value.split(',').map(|f| f.trim().parse());
+                    let min = try!(filters.next().unwrap());
+                    let mag = try!(filters.next().unwrap_or(Ok(min)));
+                    header.filter = (min, mag);
+                },
+                "repeat" => header.repeat = match &*value {
+                    "none" => (TextureWrap::ClampToEdge, TextureWrap::ClampToEdge),
+                    "x" => (TextureWrap::Repeat, TextureWrap::ClampToEdge),
+                    "y" => (TextureWrap::ClampToEdge, TextureWrap::Repeat),
+                    "xy" => (TextureWrap::Repeat, TextureWrap::Repeat),
+                    _ => return Err(AtlasError::Unexpected("unknown repeat"))
+                },
+                "pma" => header.pma = try!(value.parse()),
+                "scale" => header.scale = try!(value.parse()),
+                // fields of other packers
+                _ => ()
+            }
+        }
+        Ok(header)
+    }
+
+    fn into_page(self) -> AtlasPage {
+        AtlasPage {
+            file: self.file,
+            size: self.size,
+            format: self.format,
+            filter: self.filter,
+            repeat: self.repeat,
+            pma: self.pma,
+            scale: self.scale,
+            textures: Vec::new()
+        }
+    }
+}
+
+/// parses the `key: value` fields of a region, from legacy atlases (`xy`, `size`, `orig`,
+/// `offset`) or from Spine 4.1 ones (`bounds`, `offsets`)
+///
+/// Fields can come in any order and unknown ones are ignored. Missing fields default to an
+/// untrimmed, unrotated region at the origin of the page without index.
+fn parse_texture(name: String, values: Vec<(String, String)>) -> Result<Texture, AtlasError> {
+    let mut texture = Texture {
+        name: name,
+        rotate: Rotation::None,
+        xy: (0, 0),
+        size: (0, 0),
+        orig: (0, 0),
+        offset: (0, 0),
+        index: -1,
+        split: None,
+        pad: None
+    };
+    let mut orig = None;
+    for (key, value) in values {
+        match &*key {
+            "rotate" => texture.rotate = match &*value {
+                "false" | "0" => Rotation::None,
+                "true" | "90" => Rotation::CW90,
+                "270" => Rotation::CW270,
+                _ => return Err(AtlasError::Unexpected("rotation"))
+            },
+            "xy" => texture.xy = try!(parse_pair(&value)),
+            "size" => texture.size = try!(parse_pair(&value)),
+            "orig" => orig = Some(try!(parse_pair(&value))),
+            "offset" => texture.offset = try!(parse_pair(&value)),
+            "bounds" => {
+                let bounds = try!(parse_u16s(&value, 4));
+                texture.xy = (bounds[0], bounds[1]);
+                texture.size = (bounds[2], bounds[3]);
+            },
+            "offsets" => {
+                let offsets = try!(parse_u16s(&value, 4));
+                texture.offset = (offsets[0], offsets[1]);
+                orig = Some((offsets[2], offsets[3]));
+            },
+            "index" => texture.index = try!(value.parse()),
+            "split" => texture.split = Some(try!(parse_quad(&value))),
+            "pad" => texture.pad = Some(try!(parse_quad(&value))),
+            _ => ()
+        }
+    }
+
+    // regions which have not been trimmed have no offsets
+    texture.orig = orig.unwrap_or(texture.size);
+    Ok(texture)
+}
+
+/// Page header or region read from an atlas
+enum Entry {
+    Page(PageHeader),
+    Texture(Texture)
+}
+
+/// Iterator to parse attachments from the images of an atlas
+///
+/// Pages are separated by blank lines, textures of all pages are iterated in order. Both the
+/// legacy layout and the Spine 4.1 one are supported.
+pub struct Atlas<R: Read> {
+    /// file of the page of the last texture
+    pub file: String,
+    /// size of the page of the last texture
+    pub size: Option<(u16, u16)>,
+    /// format of the page of the last texture
+    pub format: Format,
+    /// filters of the page of the last texture
+    pub filter: (TextureFilter, TextureFilter),
+    /// wrapping of the page of the last texture
+    pub repeat: (TextureWrap, TextureWrap),
+    /// whether the page of the last texture has premultiplied alpha
+    pub pma: bool,
+    /// scale of the page of the last texture
+    pub scale: f32,
+    lines: Lines<BufReader<R>>,
+    /// line read after the fields of a page or region
+    peeked: Option<String>,
+    /// whether a blank line has been read, the next line is then the file of a new page
+    page_end: bool
+}
+
+#[cfg(feature = "compression")]
+impl Atlas<::std::io::Cursor<Vec<u8>>> {
+
+    /// consumes a reader on a gzip or zstd compressed .atlas file and create a Atlas iterator
+    ///
+    /// Uncompressed files are read as with `from_reader`.
+    pub fn from_compressed_reader<C: Read>(reader: C) -> Result<Atlas<::std::io::Cursor<Vec<u8>>>, AtlasError> {
+        let data = try!(::compression::read_decompressed(reader));
+        Atlas::from_reader(::std::io::Cursor::new(data))
+    }
 }
 
 impl<R: Read> Atlas<R> {
 
     /// consumes a reader on .atlas file and create a Atlas iterator
     pub fn from_reader(reader: R) -> Result<Atlas<R>, AtlasError> {
-        let mut lines = BufReader::new(reader).lines();
-        while let Some(line) = lines.next() {
-            let line = try!(line);
-            if line.trim().len() > 0 {
-
-                let file = line;
-                let val = try!(next_line(&mut lines));
-                let format = val["format:".len()..].trim().to_owned();
-                let val = try!(next_line(&mut lines));
-                let filter = val["filter:".len()..].trim().to_owned();
-                let val = try!(next_line(&mut lines));
-                let repeat = val["repeat:".len()..].trim().to_owned();
-
-                return Ok(Atlas {
-                    file: file,
-                    format: format,
-                    filter: filter,
-                    repeat: repeat,
-                    lines: lines
-                });
-            }
+        let mut atlas = Atlas {
+            file: String::new(),
+            size: None,
+            format: Format::RGBA8888,
+            filter: (TextureFilter::Nearest, TextureFilter::Nearest),
+            repeat: (TextureWrap::ClampToEdge, TextureWrap::ClampToEdge),
+            pma: false,
+            scale: 1f32,
+            lines: BufReader::new(reader).lines(),
+            peeked: None,
+            page_end: true
+        };
+        match atlas.next_entry() {
+            Some(Ok(_)) => Ok(atlas),
+            Some(Err(e)) => Err(e),
+            None => Err(AtlasError::Unexpected("cannot parse headers"))
         }
-        Err(AtlasError::Unexpected("cannot parse headers"))
     }
 
-    fn read_texture(&mut self, name: &str) -> Result<Texture, AtlasError> {
-        let rotate = {
-            let line = try!(next_line(&mut self.lines));
-            try!(line.trim_left()["rotate:".len()..].trim().parse())
-        };
-        let mut tuples = Vec::with_capacity(4);
-        for pattern in ["xy:", "size:", "orig:", "offset:"].into_iter() {
-            let val = try!(self.parse_tuple(pattern.len()));
-            tuples.push(val);
-        }
-        let index = {
-            let line = try!(next_line(&mut self.lines));
-            try!(line.trim_left()["index:".len()..].trim().parse())
+    /// Collects the textures of all pages by name, see `AtlasPage::regions`
+    pub fn into_map(self) -> Result<HashMap<String, Vec<Texture>>, AtlasError> {
+        let mut map = HashMap::new();
+        for texture in self {
+            let texture = try!(texture);
+            map.entry(texture.name.clone()).or_insert(Vec::new()).push(texture);
+        }
+        for textures in map.values_mut() {
+            textures.sort_by_key(|t| t.index);
+        }
+        Ok(map)
+    }
+
+    /// Converts into an iterator over the pages of the atlas, with their textures
+    pub fn pages(self) -> AtlasPages<R> {
+        let page = AtlasPage {
+            file: self.file.clone(),
+            size: self.size,
+            format: self.format,
+            filter: self.filter,
+            repeat: self.repeat,
+            pma: self.pma,
+            scale: self.scale,
+            textures: Vec::new()
         };
-        Ok(Texture {
-            name: name.to_owned(),
-            rotate: rotate,
-            xy: tuples[0],
-            size: tuples[1],
-            orig: tuples[2],
-            offset: tuples[3],
-            index: index,
-        })
+        AtlasPages {
+            atlas: self,
+            page: Some(page)
+        }
     }
 
-    fn parse_tuple(&mut self, offset: usize) -> Result<(u16, u16), AtlasError> {
-        let line = try!(next_line(&mut self.lines));
-        let mut tuple = Vec::with_capacity(2);
-        for s in line.trim_left()[offset..].split(',').take(2) {
-            let a = try!(s.trim().parse());
-            tuple.push(a);
+    /// reads the next page header or texture
+    fn next_entry(&mut self) -> Option<Result<Entry, AtlasError>> {
+        loop {
+            let line = match self.peeked.take().map(Ok).or_else(|| self.lines.next()) {
+                Some(Ok(line)) => line,
+                Some(Err(e)) => return Some(Err(AtlasError::from(e))),
+                None => return None
+            };
+            if line.trim().len() == 0 {
+                self.page_end = true;
+                continue;
+            }
+
+            let new_page = self.page_end;
+            self.page_end = false;
+            let values = match self.read_values() {
+                Ok(values) => values,
+                Err(e) => return Some(Err(e))
+            };
+            let name = line.trim().to_owned();
+            if !new_page {
+                return Some(parse_texture(name, values).map(Entry::Texture));
+            }
+            return Some(PageHeader::parse(name, values).map(|header| {
+                self.file = header.file.clone();
+                self.size = header.size;
+                self.format = header.format;
+                self.filter = header.filter;
+                self.repeat = header.repeat;
+                self.pma = header.pma;
+                self.scale = header.scale;
+                Entry::Page(header)
+            }));
         }
-        if tuple.len() != 2 {
-            Err(AtlasError::Unexpected("tuple"))
-        } else {
-            Ok((tuple[0], tuple[1]))
+    }
+
+    /// reads the `key: value` lines following the name of a page or region
+    fn read_values(&mut self) -> Result<Vec<(String, String)>, AtlasError> {
+        let mut values = Vec::new();
+        while let Some(line) = self.lines.next() {
+            let line = try!(line);
+            if line.trim().len() == 0 {
+                self.page_end = true;
+                break;
+            }
+            match line.find(':') {
+                Some(i) => values.push((line[..i].trim().to_owned(), line[i + 1..].trim().to_owned())),
+                None => {
+                    self.peeked = Some(line);
+                    break;
+                }
+            }
         }
+        Ok(values)
     }
 }
 
-fn next_line<R: Read>(lines: &mut Lines<BufReader<R>>) -> Result<String, AtlasError> {
-    match lines.next() {
-        Some(Ok(line)) => Ok(line),
-        Some(Err(e)) => Err(AtlasError::from(e)),
-        None => Err(AtlasError::Unexpected("EOF"))
+/// parses `count` comma separated integers, e.g. `2, 4`
+fn parse_u16s(value: &str, count: usize) -> Result<Vec<u16>, AtlasError> {
+    let values: Vec<u16> = try!(value.split(',').map(|v| v.trim().parse()).collect());
+    if values.len() != count {
+        Err(AtlasError::Unexpected("tuple"))
+    } else {
+        Ok(values)
     }
 }
 
+/// parses a `left, right, top, bottom` tuple
+fn parse_quad(value: &str) -> Result<[u16; 4], AtlasError> {
+    let values = try!(parse_u16s(value, 4));
+    Ok([values[0], values[1], values[2], values[3]])
+}
+
+/// parses an `x, y` tuple
+fn parse_pair(value: &str) -> Result<(u16, u16), AtlasError> {
+    let values = try!(parse_u16s(value, 2));
+    Ok((values[0], values[1]))
+}
+
 impl<R: Read> Iterator for Atlas<R> {
     type Item = Result<Texture, AtlasError>;
     fn next(&mut self) -> Option<Result<Texture, AtlasError>> {
         loop {
-            return match self.lines.next() {
-                Some(Ok(name)) => {
-                    let name = name.trim();
-                    if name.len() == 0 { continue; }
-                    Some(self.read_texture(name.trim()))
+            return match self.next_entry() {
+                Some(Ok(Entry::Page(_))) => continue,
+                Some(Ok(Entry::Texture(texture))) => Some(Ok(texture)),
+                Some(Err(e)) => Some(Err(e)),
+                None => None
+            }
+        }
+    }
+}
+
+/// Iterator over the pages of an atlas
+pub struct AtlasPages<R: Read> {
+    atlas: Atlas<R>,
+    /// page being read
+    page: Option<AtlasPage>
+}
+
+impl<R: Read> Iterator for AtlasPages<R> {
+    type Item = Result<AtlasPage, AtlasError>;
+    fn next(&mut self) -> Option<Result<AtlasPage, AtlasError>> {
+        loop {
+            match self.atlas.next_entry() {
+                Some(Ok(Entry::Texture(texture))) => {
+                    if let Some(ref mut page) = self.page {
+                        page.textures.push(texture);
+                    }
+                },
+                Some(Ok(Entry::Page(header))) => {
+                    let next = header.into_page();
+                    return self.page.take().map(Ok).map(|page| {
+                        self.page = Some(next);
+                        page
+                    });
                 },
-                Some(Err(e)) => Some(Err(AtlasError::from(e))),
-                None         => None
+                Some(Err(e)) => {
+                    self.page = None;
+                    return Some(Err(e));
+                },
+                None => return self.page.take().map(Ok)
+            }
+        }
+    }
+}
+
+/// Regions of several atlases, e.g. for a character spanning several atlas files
+#[derive(Default)]
+pub struct AtlasSet {
+    pages: Vec<AtlasPage>,
+    /// index, page and position in the page of the regions, by name and sorted by index
+    regions: HashMap<String, Vec<(i16, usize, usize)>>
+}
+
+impl AtlasSet {
+
+    /// Creates a set without any atlas
+    pub fn new() -> AtlasSet {
+        AtlasSet::default()
+    }
+
+    /// Adds the pages of an atlas
+    ///
+    /// Fails if a region has the same name and index as a region of the set, which is then left
+    /// unchanged.
+    pub fn add<R: Read>(&mut self, atlas: Atlas<R>) -> Result<(), AtlasError> {
+        let mut pages = Vec::new();
+        for page in atlas.pages() {
+            pages.push(try!(page));
+        }
+
+        let mut regions = self.regions.clone();
+        for (i, page) in pages.iter().enumerate() {
+            for (j, texture) in page.textures.iter().enumerate() {
+                let entries = regions.entry(texture.name.clone()).or_insert(Vec::new());
+                if entries.iter().any(|&(index, _, _)| index == texture.index) {
+                    return Err(AtlasError::DuplicateRegion(texture.name.clone()));
+                }
+                entries.push((texture.index, self.pages.len() + i, j));
+                entries.sort_by_key(|&(index, _, _)| index);
+            }
+        }
+        self.regions = regions;
+        self.pages.extend(pages);
+        Ok(())
+    }
+
+    /// Gets the pages of all atlases
+    pub fn pages(&self) -> &[AtlasPage] {
+        &self.pages
+    }
+
+    /// Finds a region by name, the one with the lowest index for indexed regions
+    ///
+    /// Returns the index of its page in `pages` and the region.
+    pub fn find(&self, name: &str) -> Option<(usize, &Texture)> {
+        self.regions.get(name).and_then(|entries| entries.first())
+            .map(|&(_, page, texture)| (page, &self.pages[page].textures[texture]))
+    }
+
+    /// Finds a region by name and index, e.g. a frame of a sequence, see `find`
+    pub fn find_index(&self, name: &str, index: i16) -> Option<(usize, &Texture)> {
+        self.regions.get(name).and_then(|entries| entries.iter().find(|e| e.0 == index))
+            .map(|&(_, page, texture)| (page, &self.pages[page].textures[texture]))
+    }
+}
+
+/// Packs rectangles in an atlas page, e.g. to generate the atlas of a procedurally assembled skin
+///
+/// Rectangles are placed on shelves, from the tallest to the shortest.
+pub struct Packer {
+    page_size: (u16, u16),
+    padding: u16,
+    rotation: bool
+}
+
+impl Packer {
+
+    /// Creates a packer for pages of `page_size` pixels, without padding nor rotation
+    pub fn new(page_size: (u16, u16)) -> Packer {
+        Packer {
+            page_size: page_size,
+            padding: 0,
+            rotation: false
+        }
+    }
+
+    /// Sets the pixels left between rectangles
+    pub fn set_padding(&mut self, padding: u16) {
+        self.padding = padding;
+    }
+
+    /// Sets whether rectangles taller than wide can be rotated to fit better
+    pub fn set_rotation(&mut self, rotation: bool) {
+        self.rotation = rotation;
+    }
+
+    /// Places rectangles of given names and sizes in a page of image `file`
+    ///
+    /// Textures of the page are in the order of `sizes`, the page can be saved with
+    /// `AtlasPage::write_to`. Fails if the rectangles do not fit in the page.
+    pub fn pack(&self, file: &str, sizes: &[(&str, (u16, u16))]) -> Result<AtlasPage, AtlasError> {
+        // size taken in the page by each rectangle
+        let rotated: Vec<bool> = sizes.iter().map(|&(_, size)| self.rotation && size.1 > size.0).collect();
+        let footprints: Vec<(u32, u32)> = sizes.iter().zip(rotated.iter()).map(|(&(_, size), &rotated)| {
+            if rotated { (size.1 as u32, size.0 as u32) } else { (size.0 as u32, size.1 as u32) }
+        }).collect();
+        let mut order: Vec<usize> = (0..sizes.len()).collect();
+        order.sort_by(|&a, &b| footprints[b].1.cmp(&footprints[a].1));
+
+        let (width, height) = (self.page_size.0 as u32, self.page_size.1 as u32);
+        let padding = self.padding as u32;
+        let (mut x, mut y, mut shelf) = (0, 0, 0);
+        let mut positions = vec![(0, 0); sizes.len()];
+        for i in order {
+            let (w, h) = footprints[i];
+            if x > 0 && x + w > width {
+                x = 0;
+                y += shelf + padding;
+                shelf = 0;
             }
+            if x + w > width || y + h > height {
+                return Err(AtlasError::PageOverflow(sizes[i].0.to_owned()));
+            }
+            positions[i] = (x as u16, y as u16);
+            x += w + padding;
+            shelf = shelf.max(h);
         }
+
+        let textures = sizes.iter().enumerate().map(|(i, &(name, size))| Texture {
+            name: name.to_owned(),
+            rotate: if rotated[i] { Rotation::CW90 } else { Rotation::None },
+            xy: positions[i],
+            size: size,
+            orig: size,
+            offset: (0, 0),
+            index: -1,
+            split: None,
+            pad: None
+        }).collect();
+        Ok(AtlasPage {
+            file: file.to_owned(),
+            size: Some(self.page_size),
+            format: Format::RGBA8888,
+            filter: (TextureFilter::Nearest, TextureFilter::Nearest),
+            repeat: (TextureWrap::ClampToEdge, TextureWrap::ClampToEdge),
+            pma: false,
+            scale: 1f32,
+            textures: textures
+        })
     }
 }
 
@@ -140,7 +773,13 @@ pub enum AtlasError {
     /// error when parsing u16 or i16
     ParseIntError(::std::num::ParseIntError),
     /// error when parsing boolean
-    ParseBoolError(::std::str::ParseBoolError)
+    ParseBoolError(::std::str::ParseBoolError),
+    /// error when parsing f32
+    ParseFloatError(::std::num::ParseFloatError),
+    /// region defined by several atlases of a set, with its name
+    DuplicateRegion(String),
+    /// rectangle which does not fit in the page when packing, with its name
+    PageOverflow(String)
 }
 
 impl fmt::Display for AtlasError {
@@ -154,6 +793,9 @@ impl Error for AtlasError {
         match *self {
             AtlasError::ParseIntError(_) => "error parsing integer",
             AtlasError::ParseBoolError(_) => "error parsing boolean",
+            AtlasError::ParseFloatError(_) => "error parsing float",
+            AtlasError::DuplicateRegion(_) => "region defined by several atlases",
+            AtlasError::PageOverflow(_) => "rectangle does not fit in the page",
             AtlasError::Unexpected(_) => "unexpected error",
             AtlasError::IoError(_) => "error reading atlas file",
         }
@@ -165,6 +807,9 @@ impl fmt::Debug for AtlasError {
         match *self {
             AtlasError::ParseIntError(ref e) => write!(f, "Cannot parse integer: {:?}", e),
             AtlasError::ParseBoolError(ref e) => write!(f, "Cannot parse boolean: {:?}", e),
+            AtlasError::ParseFloatError(ref e) => write!(f, "Cannot parse float: {:?}", e),
+            AtlasError::DuplicateRegion(ref name) => write!(f, "Region '{}' is defined several times", name),
+            AtlasError::PageOverflow(ref name) => write!(f, "Rectangle '{}' does not fit in the page", name),
             AtlasError::Unexpected(s) => write!(f, "Unexpected error: {}", s),
             AtlasError::IoError(ref e) => write!(f, "Error reading atlas file: {:?}", e),
         }
@@ -188,3 +833,9 @@ impl From<ParseBoolError> for AtlasError {
         AtlasError::ParseBoolError(error)
     }
 }
+
+impl From<::std::num::ParseFloatError> for AtlasError {
+    fn from(error: ::std::num::ParseFloatError) -> AtlasError {
+        AtlasError::ParseFloatError(error)
+    }
+}
@@ -0,0 +1,126 @@
+//! Module to load a skeleton along with its atlas
+//!
+//! Sprites of a bundle carry the page and texture coordinates of their region, so that they can
+//! be drawn without any further lookup.
+
+use atlas::{Atlas, AtlasError, AtlasPage, AtlasSet};
+use skeleton::Skeleton;
+use skeleton::error::SkeletonError;
+use std::io::Read;
+use std::fmt;
+use std::error::Error;
+
+/// Skeleton bound to the atlases of its images
+pub struct SpineBundle {
+    skeleton: Skeleton,
+    atlases: AtlasSet
+}
+
+impl SpineBundle {
+
+    /// Parses a skeleton and its atlas, and binds the regions to the attachments
+    ///
+    /// Fails if an image drawn by the skeleton has no region in the atlas.
+    pub fn load<S: Read, A: Read>(skeleton: S, atlas: A) -> Result<SpineBundle, BundleError> {
+        let skeleton = try!(Skeleton::from_reader(skeleton));
+        let mut atlases = AtlasSet::new();
+        try!(atlases.add(try!(Atlas::from_reader(atlas))));
+        SpineBundle::from_parts(skeleton, atlases)
+    }
+
+    /// Binds a skeleton to atlases already loaded, e.g. spanning several files
+    ///
+    /// Fails if an image drawn by the skeleton has no region, or if the page of its region has
+    /// no size to compute texture coordinates.
+    pub fn from_parts(mut skeleton: Skeleton, atlases: AtlasSet) -> Result<SpineBundle, BundleError> {
+        for image in skeleton.get_images_names() {
+            match atlases.find(image) {
+                Some((page, _)) => if atlases.pages()[page].size.is_none() {
+                    return Err(BundleError::PageSizeNotFound(atlases.pages()[page].file.clone()));
+                },
+                None => return Err(BundleError::RegionNotFound(image.to_owned()))
+            }
+        }
+        skeleton.bind_atlas_set(&atlases);
+        Ok(SpineBundle {
+            skeleton: skeleton,
+            atlases: atlases
+        })
+    }
+
+    /// Gets the skeleton, with the regions bound to its attachments
+    pub fn get_skeleton(&self) -> &Skeleton {
+        &self.skeleton
+    }
+
+    /// Gets the atlases of the skeleton
+    pub fn get_atlases(&self) -> &AtlasSet {
+        &self.atlases
+    }
+
+    /// Gets the atlas pages, indexed by `Sprite::page`
+    pub fn get_pages(&self) -> &[AtlasPage] {
+        self.atlases.pages()
+    }
+
+    /// Splits the bundle into its skeleton and atlases
+    pub fn into_parts(self) -> (Skeleton, AtlasSet) {
+        (self.skeleton, self.atlases)
+    }
+}
+
+/// Error that can happen while loading a bundle
+pub enum BundleError {
+
+    /// Error while parsing the skeleton
+    SkeletonError(SkeletonError),
+
+    /// Error while parsing the atlas
+    AtlasError(AtlasError),
+
+    /// An image drawn by the skeleton has no region in the atlases.
+    RegionNotFound(String),
+
+    /// The page of a region has no size.
+    PageSizeNotFound(String),
+}
+
+impl fmt::Debug for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BundleError::SkeletonError(ref e) => write!(f, "Cannot load skeleton: {:?}", e),
+            BundleError::AtlasError(ref e) => write!(f, "Cannot load atlas: {:?}", e),
+            BundleError::RegionNotFound(ref name) => write!(f, "Cannot find region '{}'", name),
+            BundleError::PageSizeNotFound(ref file) => write!(f, "Cannot find size of page '{}'", file),
+        }
+    }
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self, formatter)
+    }
+}
+
+impl Error for BundleError {
+    fn description(&self) -> &str {
+        match *self {
+            BundleError::SkeletonError(_) => "error while loading skeleton",
+            BundleError::AtlasError(_) => "error while loading atlas",
+            BundleError::RegionNotFound(_) => "region cannot be found in atlases",
+            BundleError::PageSizeNotFound(_) => "page size cannot be found in atlas",
+        }
+    }
+}
+
+impl From<SkeletonError> for BundleError {
+    fn from(error: SkeletonError) -> BundleError {
+        BundleError::SkeletonError(error)
+    }
+}
+
+impl From<AtlasError> for BundleError {
+    fn from(error: AtlasError) -> BundleError {
+        BundleError::AtlasError(error)
+    }
+}
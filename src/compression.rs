@@ -0,0 +1,28 @@
+//! Module to read gzip and zstd compressed documents, with the `compression` feature
+
+use flate2::read::GzDecoder;
+use ruzstd::decoding::StreamingDecoder;
+use std::io::{self, Read};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Reads all the data of a reader, decompressed if it is gzip or zstd compressed
+///
+/// The compression is detected from the first bytes of the data, other data is returned as is.
+pub fn read_decompressed<R: Read>(mut reader: R) -> io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    try!(reader.read_to_end(&mut data));
+
+    let mut decompressed = Vec::new();
+    if data.starts_with(&GZIP_MAGIC) {
+        try!(GzDecoder::new(&*data).read_to_end(&mut decompressed));
+    } else if data.starts_with(&ZSTD_MAGIC) {
+        let mut decoder = try!(StreamingDecoder::new(&*data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)));
+        try!(decoder.read_to_end(&mut decompressed));
+    } else {
+        return Ok(data);
+    }
+    Ok(decompressed)
+}
@@ -1,20 +1,83 @@
-use from_json;
-use std::collections::HashMap;
+use indexmap::IndexMap;
+use serde::de::{self, Deserialize, Deserializer};
+use serde_json::value::RawValue;
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Deref;
+
+/// Name borrowed from the document, unless it contains escape sequences
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Name<'a>(Cow<'a, str>);
+
+impl<'a> Name<'a> {
+    pub fn into_owned(self) -> String {
+        self.0.into_owned()
+    }
+}
 
-#[derive(Debug, Clone)]
-pub struct Document {
-    pub bones: Option<Vec<Bone>>,
-    pub slots: Option<Vec<Slot>>,
-    pub skins: Option<HashMap<String, HashMap<String, HashMap<String, Attachment>>>>,
-    pub animations: Option<HashMap<String, Animation>>,
+impl<'a> Deref for Name<'a> {
+    type Target = str;
+    fn deref(&self) -> &str {
+        &self.0
+    }
 }
 
-derive_from_json!(Document, bones, slots, skins, animations);
+impl<'de: 'a, 'a> Deserialize<'de> for Name<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Name<'a>, D::Error> {
+        struct NameVisitor;
 
-#[derive(Debug, Clone)]
-pub struct Bone {
-    pub name: String,
-    pub parent: Option<String>,
+        impl<'de> de::Visitor<'de> for NameVisitor {
+            type Value = Name<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Name<'de>, E> {
+                Ok(Name(Cow::Borrowed(v)))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Name<'de>, E> {
+                Ok(Name(Cow::Owned(v.to_owned())))
+            }
+
+            fn visit_string<E: de::Error>(self, v: String) -> Result<Name<'de>, E> {
+                Ok(Name(Cow::Owned(v)))
+            }
+        }
+
+        deserializer.deserialize_str(NameVisitor)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Document<'a> {
+    #[serde(borrow)]
+    pub bones: Option<Vec<Bone<'a>>>,
+    #[serde(borrow)]
+    pub slots: Option<Vec<Slot<'a>>>,
+    #[serde(borrow)]
+    pub skins: Option<IndexMap<Name<'a>, IndexMap<Name<'a>, IndexMap<Name<'a>, Attachment<'a>>>>>,
+    pub events: Option<IndexMap<String, Event>>,
+    /// animations are kept as json until they are requested, see `Animation`
+    #[serde(borrow)]
+    pub animations: Option<IndexMap<Name<'a>, Box<RawValue>>>,
+}
+
+/// Animations exported without their rig, other sections are ignored
+#[derive(Debug, Deserialize)]
+pub struct AnimationsDocument<'a> {
+    #[serde(borrow)]
+    pub animations: Option<IndexMap<Name<'a>, Box<RawValue>>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bone<'a> {
+    #[serde(borrow)]
+    pub name: Name<'a>,
+    #[serde(borrow)]
+    pub parent: Option<Name<'a>>,
     pub length: Option<f32>,
     pub x: Option<f32>,
     pub y: Option<f32>,
@@ -25,90 +88,76 @@ pub struct Bone {
     pub inherit_rotation: Option<bool>
 }
 
-derive_from_json!(Bone, name, parent, length, x, y, scale_x as "scaleX", scale_y as "scaleY",
-                  rotation, inherit_scale as "inheritScale", inherit_rotation as "inheritRotation");
-
-#[derive(Debug, Clone)]
-pub struct Slot {
-    pub name: String,
-    pub bone: String,
-    pub color: Option<String>,
-    pub attachment: Option<String>,
+#[derive(Debug, Clone, Deserialize)]
+pub struct Slot<'a> {
+    #[serde(borrow)]
+    pub name: Name<'a>,
+    #[serde(borrow)]
+    pub bone: Name<'a>,
+    #[serde(borrow)]
+    pub color: Option<Name<'a>>,
+    #[serde(borrow)]
+    pub attachment: Option<Name<'a>>,
 }
 
-derive_from_json!(Slot, name, bone, color, attachment);
-
-#[derive(Debug, Clone)]
-pub struct Attachment {
-    pub name: Option<String>,
+#[derive(Debug, Clone, Deserialize)]
+pub struct Attachment<'a> {
+    #[serde(borrow)]
+    pub name: Option<Name<'a>>,
+    #[serde(rename = "type")]
     pub type_: Option<AttachmentType>,
     pub x: Option<f32>,
     pub y: Option<f32>,
+    #[serde(rename = "scaleX")]
     pub scale_x: Option<f32>,
+    #[serde(rename = "scaleY")]
     pub scale_y: Option<f32>,
     pub rotation: Option<f32>,
     pub width: Option<f32>,
     pub height: Option<f32>,
     pub fps: Option<f32>,
     pub mode: Option<String>,       // TODO: add enum forward, backward etc ...
-    //vertices: Option<Vec<??>>     // TODO: ?
+    pub vertices: Option<Vec<f32>>,
 }
 
-derive_from_json!(Attachment, name, type_ as "type", x, y,
-                  scale_x as "scaleX", scale_y as "scaleY", rotation, width, height, fps, mode);
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum AttachmentType {
     Region,
     RegionSequence,
     BoundingBox,
 }
 
-impl from_json::FromJson for AttachmentType {
-    fn from_json(input: &from_json::Json) -> Result<AttachmentType, from_json::FromJsonError> {
-        use from_json::FromJson;
-
-        let string: String = try!(FromJson::from_json(input));
-
-        match &*string {
-            "region" => Ok(AttachmentType::Region),
-            "regionsequence" => Ok(AttachmentType::RegionSequence),
-            "boundingbox" => Ok(AttachmentType::BoundingBox),
-            _ => Err(from_json::FromJsonError::ExpectError("AttachmentType", input.clone()))
-        }
-    }
-}
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Event {
-    pub name: String,
+    #[serde(rename = "int")]
     pub int_: Option<i32>,
+    #[serde(rename = "float")]
     pub float_: Option<f32>,
     pub string: Option<String>,
+    pub audio: Option<String>,
+    pub volume: Option<f32>,
+    pub balance: Option<f32>,
 }
 
-derive_from_json!(Event, name, int_ as "int", float_ as "float", string);
-
-#[derive(Debug, Clone)]
-pub struct Animation {
-    pub bones: Option<HashMap<String, BoneTimeline>>,
-    pub slots: Option<HashMap<String, SlotTimeline>>,
+#[derive(Debug, Clone, Deserialize)]
+pub struct Animation<'a> {
+    #[serde(borrow)]
+    pub bones: Option<IndexMap<Name<'a>, BoneTimeline>>,
+    #[serde(borrow)]
+    pub slots: Option<IndexMap<Name<'a>, SlotTimeline>>,
     pub events: Option<Vec<EventKeyframe>>,
     pub draworder: Option<Vec<DrawOrderTimeline>>,
 }
 
-derive_from_json!(Animation, bones, slots, events, draworder);
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct BoneTimeline {
     pub translate: Option<Vec<BoneTranslateTimeline>>,
     pub rotate: Option<Vec<BoneRotateTimeline>>,
     pub scale: Option<Vec<BoneScaleTimeline>>,
 }
 
-derive_from_json!(BoneTimeline, translate, rotate, scale);
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct BoneTranslateTimeline {
     pub time: f32,
     pub curve: Option<TimelineCurve>,
@@ -116,18 +165,14 @@ pub struct BoneTranslateTimeline {
     pub y: Option<f32>,
 }
 
-derive_from_json!(BoneTranslateTimeline, time, curve, x, y);
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct BoneRotateTimeline {
     pub time: f32,
     pub curve: Option<TimelineCurve>,
     pub angle: Option<f32>,
 }
 
-derive_from_json!(BoneRotateTimeline, time, curve, angle);
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct BoneScaleTimeline {
     pub time: f32,
     pub curve: Option<TimelineCurve>,
@@ -135,8 +180,6 @@ pub struct BoneScaleTimeline {
     pub y: Option<f32>,
 }
 
-derive_from_json!(BoneScaleTimeline, time, curve, x, y);
-
 #[derive(Debug, Clone)]
 pub enum TimelineCurve {
     CurveLinear,
@@ -144,73 +187,66 @@ pub enum TimelineCurve {
     CurveBezier(Vec<f32>),
 }
 
-impl from_json::FromJson for TimelineCurve {
-    fn from_json(input: &from_json::Json) -> Result<TimelineCurve, from_json::FromJsonError> {
-        use from_json::FromJson;
+impl<'de> Deserialize<'de> for TimelineCurve {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<TimelineCurve, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Curve {
+            Name(String),
+            Bezier(Vec<f32>)
+        }
 
-        if input.is_array() {
-            Ok(TimelineCurve::CurveBezier(try!(FromJson::from_json(input))))
-        } else {
-            let curve_type: String = try!(FromJson::from_json(input));
-            match &curve_type[..] {
-                "linear" => Ok(TimelineCurve::CurveLinear),
-                "stepped" => Ok(TimelineCurve::CurveStepped),
-                _ => Err(from_json::FromJsonError::ExpectError(
-                    "Timeline curve must be either linear, stepped or an array", input.clone()))
-            }
+        match try!(Curve::deserialize(deserializer)) {
+            Curve::Bezier(points) => Ok(TimelineCurve::CurveBezier(points)),
+            Curve::Name(ref name) if name == "linear" => Ok(TimelineCurve::CurveLinear),
+            Curve::Name(ref name) if name == "stepped" => Ok(TimelineCurve::CurveStepped),
+            Curve::Name(name) => Err(de::Error::custom(format!(
+                "unknown timeline curve '{}', expected linear, stepped or an array", name)))
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct SlotTimeline {
     pub attachment: Option<Vec<SlotAttachmentTimeline>>,
     pub color: Option<Vec<SlotColorTimeline>>,
 }
 
-derive_from_json!(SlotTimeline, attachment, color);
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct SlotAttachmentTimeline {
     pub time: f32,
     pub name: Option<String>,
 }
 
-derive_from_json!(SlotAttachmentTimeline, time, name);
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct SlotColorTimeline {
     pub time: f32,
     pub color: Option<String>,
     pub curve: Option<TimelineCurve>,
 }
 
-derive_from_json!(SlotColorTimeline, time, color, curve);
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct EventKeyframe {
     pub time: f32,
-    name: String,
-    int_: Option<i32>,
-    float_: Option<f32>,
-    string_: Option<String>,
+    pub name: String,
+    #[serde(rename = "int")]
+    pub int_: Option<i32>,
+    #[serde(rename = "float")]
+    pub float_: Option<f32>,
+    #[serde(rename = "string")]
+    pub string_: Option<String>,
+    pub volume: Option<f32>,
+    pub balance: Option<f32>,
 }
 
-derive_from_json!(EventKeyframe, time, name, int_ as "int", float_ as "float",
-                  string_ as "string");
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct DrawOrderTimeline {
     pub time: f32,
     offsets: Option<Vec<DrawOrderTimelineOffset>>,
 }
 
-derive_from_json!(DrawOrderTimeline, time, offsets);
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct DrawOrderTimelineOffset {
     slot: String,
     offset: i32,
 }
-
-derive_from_json!(DrawOrderTimelineOffset, slot, offset);
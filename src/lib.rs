@@ -4,7 +4,9 @@
 //!
 //! ## Step 1: loading the document
 //!
-//! Call `skeleton::Skeleton::from_reader` to parse the content of a document.
+//! Call `skeleton::Skeleton::from_reader` to parse the content of a document, or
+//! `Skeleton::from_file`, `Skeleton::from_slice` and `str::parse` for documents in files and in
+//! memory.
 //!
 //! This function returns an `Err` if the document is not valid JSON or if something is not
 //!  recognized in it.
@@ -14,6 +16,8 @@
 //! # use std::path::Path;
 //! let skeleton = spine::skeleton::Skeleton::from_reader(File::open(&Path::new("skeleton.json")).unwrap())
 //!     .unwrap();
+//! // equivalent to
+//! let skeleton = spine::skeleton::Skeleton::from_file("skeleton.json").unwrap();
 //! ```
 //!
 //! ## Step 2: preparing for drawing
@@ -43,7 +47,7 @@
 //! You then have 2 methods to get the sprites you need to draw:
 //! - directly call `animation.interpolate` for a given time
 //! - use a built-in `AnimationIter` iterator by calling `animation.run()` to run the animation
-//! with a constant period
+//! with a constant period, call `.timed()` on it to get the time of each item as well
 //!
 //! Both methods returns a `Sprites` iterator over the `Sprite`s do be drawn.
 //!
@@ -75,13 +79,38 @@
 //! }
 //! ```
 //!
+//! Each sprite also has the world-space corners of its attachment quad in `sprite.positions`,
+//! which can be used directly as vertex positions.
+//!
+//! To animate many characters from the same document, share the skeleton in an `Arc` and create
+//! one `skeleton::animation::SkeletonInstance` per character. Each instance has its own skin,
+//! animation, pose and time.
+//!
 
 #![deny(missing_docs)]
 
+extern crate serde;
 #[macro_use]
-extern crate from_json;
-extern crate rustc_serialize as serialize;
+extern crate serde_derive;
+extern crate serde_json;
+extern crate serde_path_to_error;
+extern crate indexmap;
+extern crate smallvec;
+#[cfg(feature = "compression")]
+extern crate flate2;
+#[cfg(feature = "compression")]
+extern crate ruzstd;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "wide")]
+extern crate wide;
 
 mod json;
 pub mod skeleton;
 pub mod atlas;
+pub mod bundle;
+pub mod render;
+#[cfg(feature = "svg")]
+pub mod svg;
+#[cfg(feature = "compression")]
+pub mod compression;
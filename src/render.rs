@@ -0,0 +1,94 @@
+//! Module to generate vertex and index buffers from sprites
+//!
+//! All visible sprites are written as quads, in setup draw order, so that a whole frame can be
+//! uploaded and drawn at once.
+
+use atlas::{trim_quad, Texture};
+use skeleton::animation::{SkinAnimation, Sprites};
+use std::collections::HashMap;
+
+/// Vertex of a sprite quad
+#[derive(Debug, Clone, Copy)]
+pub struct Vertex {
+    /// world-space position
+    pub position: [f32; 2],
+    /// normalized texture coordinates
+    pub uv: [f32; 2],
+    /// color
+    pub color: [u8; 4]
+}
+
+/// Buffers generator for the regions of an atlas page
+pub struct BufferBuilder {
+    /// regions texture coordinates and trim, by name
+    regions: HashMap<String, ([[f32; 2]; 4], [[f32; 2]; 2])>
+}
+
+impl BufferBuilder {
+
+    /// Creates a new generator from the textures of an atlas page of `page_size` pixels
+    pub fn new<I: IntoIterator<Item=Texture>>(textures: I, page_size: (u16, u16)) -> BufferBuilder {
+        BufferBuilder {
+            regions: textures.into_iter().map(|t| {
+                let region = (t.uvs(page_size), t.trim());
+                (t.name, region)
+            }).collect()
+        }
+    }
+
+    /// Fills the buffers with the sprites of an animation at given time
+    ///
+    /// Returns `false`, leaving the buffers empty, if time is out of the animation, or if the
+    /// sprites do not fit in 16-bit indices (see `fill_sprites`).
+    pub fn fill(&self, animation: &SkinAnimation, time: f32,
+                vertices: &mut Vec<Vertex>, indices: &mut Vec<u16>) -> bool
+    {
+        match animation.interpolate(time) {
+            Some(sprites) => self.fill_sprites(sprites, vertices, indices),
+            None => {
+                vertices.clear();
+                indices.clear();
+                false
+            }
+        }
+    }
+
+    /// Fills the buffers with sprites
+    ///
+    /// The buffers are cleared first, so that they can be reused from frame to frame.
+    /// Texture coordinates bound with `Skeleton::bind_atlas` take precedence over the ones of
+    /// this generator, sprites with neither are skipped. Quads of trimmed regions of this
+    /// generator are shrunk like the ones of bound regions.
+    ///
+    /// Indices are 16 bits, so that the buffers hold at most 16384 quads: returns `false`,
+    /// keeping the quads written so far, at the first sprite which does not fit.
+    pub fn fill_sprites(&self, sprites: Sprites, vertices: &mut Vec<Vertex>, indices: &mut Vec<u16>) -> bool {
+        vertices.clear();
+        indices.clear();
+        let len = sprites.len();
+        vertices.reserve(len * 4);
+        indices.reserve(len * 6);
+        for sprite in sprites {
+            let (uvs, positions) = match sprite.uvs {
+                Some(uvs) => (uvs, sprite.positions),
+                None => match self.regions.get(sprite.attachment) {
+                    Some(&(uvs, trim)) => (uvs, trim_quad(sprite.positions, trim)),
+                    None => continue
+                }
+            };
+            if vertices.len() + 4 > u16::MAX as usize + 1 {
+                return false;
+            }
+            let base = vertices.len() as u16;
+            for (position, uv) in positions.iter().zip(uvs.iter()) {
+                vertices.push(Vertex {
+                    position: *position,
+                    uv: *uv,
+                    color: sprite.color
+                });
+            }
+            indices.extend([0, 1, 2, 0, 2, 3].iter().map(|i| base + i));
+        }
+        true
+    }
+}
@@ -1,227 +1,2054 @@
 //! Module to interpolate animated sprites
 
+use indexmap::IndexMap;
+use json;
 use skeleton;
 use skeleton::error::SkeletonError;
+use skeleton::TimelineCursor;
+use skeleton::events::Event;
+use skeleton::mask::BoneMask;
+use skeleton::pose::Pose;
+use skeleton::timelines::Interpolate;
+use std::borrow::Cow;
+use std::cell::Cell;
 use std::collections::HashMap;
+use std::iter::{Chain, Enumerate, Peekable};
 use std::slice::Iter;
+use std::sync::Arc;
+
+/// Attachment of a skin slot: whether it is in default skin, index of the skin slot, index of
+/// the attachment in the skin slot and name
+#[derive(Clone)]
+struct AttachmentKey {
+    default_skin: bool,
+    index: usize,
+    attachment: usize,
+    name: String
+}
 
 /// Wrapper on attachment depending whether slot attachment is animated or not
-enum AttachmentWrapper<'a> {
-    Static(Option<&'a skeleton::Attachment>),
-    Dynamic(Option<&'a skeleton::Attachment>, HashMap<&'a str, Option<&'a skeleton::Attachment>>),
+///
+/// Animated attachments are resolved for each keyframe of the attachment timeline, so that
+/// sampling them is only indexing.
+#[derive(Clone)]
+enum AttachmentWrapper {
+    Static(Option<AttachmentKey>),
+    Dynamic(Option<AttachmentKey>, Vec<Option<AttachmentKey>>),
 }
 
-/// Struct to handle animated skin and calculate sprites
-pub struct SkinAnimation<'a> {
-    anim_bones: Vec<(&'a skeleton::Bone, Option<&'a skeleton::timelines::BoneTimeline>)>,
-    anim_slots: Vec<(&'a skeleton::Slot, AttachmentWrapper<'a>, Option<&'a skeleton::timelines::SlotTimeline>)>,
-    duration: f32
+/// Searches a slot attachment in a skin, then in default skin
+fn find_attachment(skin: &skeleton::Skin, default_skin: &skeleton::Skin, slot_index: usize, name: &str)
+    -> Option<AttachmentKey>
+{
+    let key = |default_skin, (index, attachment)| AttachmentKey {
+        default_skin: default_skin,
+        index: index,
+        attachment: attachment,
+        name: name.to_owned()
+    };
+    skin.find_index(slot_index, name).map(|index| key(false, index))
+        .or_else(|| default_skin.find_index(slot_index, name).map(|index| key(true, index)))
 }
 
-/// Interpolated slot with attachment and color
-#[derive(Debug)]
-pub struct Sprite<'a> {
-    /// attachment name
-    pub attachment: &'a str,
-    /// color
-    pub color: [u8; 4],
-    /// srt
-    pub srt: skeleton::SRT
+/// Searches the attachment shown instead of the skin one in a slot, `None` to hide the slot
+fn find_override(skeleton: &skeleton::Skeleton, binding: &Binding, slot_index: usize, name: Option<&str>)
+    -> Result<Option<AttachmentKey>, SkeletonError>
+{
+    match name {
+        Some(name) => {
+            let (skin, default_skin, _) = binding.resolve(skeleton);
+            find_attachment(skin, default_skin, slot_index, name).map(Some)
+                .ok_or_else(|| SkeletonError::AttachmentNotFound(name.to_owned()))
+        },
+        None => Ok(None)
+    }
 }
 
-impl<'a> SkinAnimation<'a> {
+/// Skin and animation bound to the skeleton bones and slots, without borrowing the skeleton
+#[derive(Clone)]
+struct Binding {
+    skin: String,
+    animation: Option<String>,
+    /// index of the animation bone timeline, for each bone
+    bones: Vec<Option<usize>>,
+    /// attachment and index of the animation slot timeline, for each slot
+    slots: Vec<(AttachmentWrapper, Option<usize>)>,
+    duration: f32,
+    /// whether world transforms are mirrored horizontally and vertically
+    flip: [bool; 2],
+    /// alpha multiplier of all sprites
+    opacity: f32,
+    /// skeleton-space transform of the root, when mounted on a bone of another skeleton
+    root: Option<skeleton::SRT>
+}
 
-    /// Iterator<Item=Vec<CalculatedSlot>> where item are modified with timelines
-    pub fn new(skeleton: &'a skeleton::Skeleton, skin: &str, animation: Option<&str>)
-        -> Result<SkinAnimation<'a>, SkeletonError>
+impl Binding {
+
+    fn new(skeleton: &skeleton::Skeleton, skin: &str, animation: Option<&str>)
+        -> Result<Binding, SkeletonError>
     {
         // search all attachments defined by the skin name (use 'default' skin if not found)
-        let skin = try!(skeleton.get_skin(skin));
+        let skin_data = try!(skeleton.get_skin(skin));
         let default_skin = try!(skeleton.get_skin("default"));
 
         // get animation
-        let (animation, duration) = if let Some(animation) = animation {
-            let anim = try!(skeleton.animations.get(animation)
-                .ok_or_else(|| SkeletonError::AnimationNotFound(animation.to_owned())));
-            (Some(anim), anim.duration)
-        } else {
-            (None, 0f32)
+        let anim = match animation {
+            Some(animation) => Some(try!(skeleton.animation(animation))),
+            None => None
         };
 
         // get bone related data
-        let anim_bones = skeleton.bones.iter().enumerate().map(|(i, b)|
-            (b, animation.and_then(|anim| anim.bones.iter()
-                .find(|&&(idx, _)| idx == i).map(|&(_, ref a)| a)))).collect();
+        let bones = (0..skeleton.bones.len()).map(|i|
+            anim.and_then(|anim| anim.bones.iter().position(|&(idx, _)| idx == i))).collect();
 
-        let find_attach = |i: usize, name: &str| skin.find(i, name).or_else(|| default_skin.find(i, name));
+        let find_attach = |i: usize, name: &str| find_attachment(skin_data, default_skin, i, name);
 
         // get slot related data
-        let anim_slots = skeleton.slots.iter().enumerate().map(|(i, s)| {
+        let slots = skeleton.slots.iter().enumerate().map(|(i, s)| {
 
-            let anim = animation.and_then(|anim|
-                anim.slots.iter().find(|&&(idx, _)| idx == i ).map(|&(_, ref anim)| anim));
+            let timeline = anim.and_then(|anim| anim.slots.iter().position(|&(idx, _)| idx == i));
 
             let slot_attach = s.attachment.as_ref().and_then(|name| find_attach(i, &name));
-            let attach = match anim.map(|anim| anim.get_attachment_names()) {
-                Some(names) => {
-                    if names.is_empty() {
-                         AttachmentWrapper::Static(slot_attach)
-                    } else {
-                        let attachments = names.iter().map(|&name|(name, find_attach(i, name))).collect();
-                        AttachmentWrapper::Dynamic(slot_attach, attachments)
-                    }
+            let keys = timeline.map(|t| anim.unwrap().slots[t].1.attachment_keys());
+            let attach = match keys {
+                Some(ref keys) if !keys.is_empty() => {
+                    let attachments = keys.iter().map(|&(_, name)| match name {
+                        Some(name) => find_attach(i, name),
+                        None => slot_attach.clone()
+                    }).collect();
+                    AttachmentWrapper::Dynamic(slot_attach, attachments)
                 },
-                None => AttachmentWrapper::Static(slot_attach)
+                _ => AttachmentWrapper::Static(slot_attach)
             };
-            (s, attach, anim)
+            (attach, timeline)
         }).collect();
 
-        Ok(SkinAnimation {
-            duration: duration,
-            anim_bones: anim_bones,
-            anim_slots: anim_slots,
+        Ok(Binding {
+            skin: skin.to_owned(),
+            animation: animation.map(|a| a.to_owned()),
+            bones: bones,
+            slots: slots,
+            duration: anim.map(|anim| anim.duration).unwrap_or(0f32),
+            flip: [false, false],
+            opacity: 1f32,
+            root: None
         })
     }
 
-    /// Gets duration of the longest timeline in the animation
-    pub fn get_duration(&self) -> f32 {
-        self.duration
+    /// removes the timelines of the bones and slots out of the mask
+    fn mask(&mut self, mask: &BoneMask) {
+        for (i, timeline) in self.bones.iter_mut().enumerate() {
+            if !mask.contains_bone(i) {
+                *timeline = None;
+            }
+        }
+        for (i, slot) in self.slots.iter_mut().enumerate() {
+            if !mask.contains_slot(i) {
+                let attach = match slot.0 {
+                    AttachmentWrapper::Static(ref attach) => attach.clone(),
+                    AttachmentWrapper::Dynamic(ref attach, _) => attach.clone()
+                };
+                *slot = (AttachmentWrapper::Static(attach), None);
+            }
+        }
+    }
+
+    /// gets skin, default skin and animation from the skeleton the binding has been created from
+    fn resolve<'a>(&self, skeleton: &'a skeleton::Skeleton)
+        -> (&'a skeleton::Skin, &'a skeleton::Skin, Option<&'a skeleton::Animation>)
+    {
+        (&skeleton.skins[&self.skin], &skeleton.skins["default"],
+         self.animation.as_ref().map(|a| skeleton.animation(a).expect("bound animations are parsed")))
     }
 
-    /// gets all bones srts at given time
-    fn get_bones_srts(&self, time: f32) -> Vec<skeleton::SRT> {
+    /// resolves the binding against the skeleton it has been created from
+    fn bind<'a>(&'a self, skeleton: &'a skeleton::Skeleton) -> Bound<'a> {
+        let (skin, default_skin, animation) = self.resolve(skeleton);
+        Bound {
+            skeleton: skeleton,
+            skin: skin,
+            default_skin: default_skin,
+            animation: animation,
+            binding: self,
+            cursor: None
+        }
+    }
+}
 
-        let mut srts: Vec<skeleton::SRT> = Vec::with_capacity(self.anim_bones.len());
-        for &(b, anim) in &self.anim_bones {
+/// Keyframes positions in the timelines of an animation at a time, see `TimelineCursor`
+#[derive(Clone, Default)]
+struct AnimationCursor {
+    time: f32,
+    /// cursors of the bone and slot timelines, in animation order
+    bones: Vec<TimelineCursor>,
+    slots: Vec<TimelineCursor>
+}
 
-            // starts with setup pose
-            let mut srt = b.srt.clone();
-            let mut rotation = 0.0;
+impl AnimationCursor {
 
-            // add animation srt
-            if let Some(anim_srt) = anim.map(|anim| anim.srt(time)) {
-                srt.position[0] += anim_srt.position[0];
-                srt.position[1] += anim_srt.position[1];
-                rotation += anim_srt.rotation;
-                srt.scale[0] *= anim_srt.scale[0];
-                srt.scale[1] *= anim_srt.scale[1];
+    /// moves the cursors to a time, the animation must be the one of the previous moves
+    fn seek(&mut self, animation: Option<&skeleton::Animation>, time: f32) {
+        if let Some(animation) = animation {
+            self.bones.resize(animation.bones.len(), TimelineCursor::default());
+            self.slots.resize(animation.slots.len(), TimelineCursor::default());
+            for (&(_, ref timeline), cursor) in animation.bones.iter().zip(self.bones.iter_mut()) {
+                timeline.seek(time, cursor);
             }
-
-            // inherit world from parent srt
-            if let Some(ref parent_srt) = b.parent_index.and_then(|p| srts.get(p)) {
-                srt.position = parent_srt.transform(srt.position);
-                if b.inherit_rotation {
-                    rotation += parent_srt.rotation;
-                }
-                if b.inherit_scale {
-                    srt.scale[0] *= parent_srt.scale[0];
-                    srt.scale[1] *= parent_srt.scale[1];
-                }
+            for (&(_, ref timeline), cursor) in animation.slots.iter().zip(self.slots.iter_mut()) {
+                timeline.seek(time, cursor);
             }
+        }
+        self.time = time;
+    }
+}
 
-            // re-calculate sin/cos only if rotation has changed
-            if rotation != 0.0 {
-                srt.rotation += rotation;
-                srt.cos = srt.rotation.cos();
-                srt.sin = srt.rotation.sin();
-            }
-            srts.push(srt)
+/// Binding with its skeleton data
+#[derive(Clone, Copy)]
+struct Bound<'a> {
+    skeleton: &'a skeleton::Skeleton,
+    skin: &'a skeleton::Skin,
+    default_skin: &'a skeleton::Skin,
+    animation: Option<&'a skeleton::Animation>,
+    binding: &'a Binding,
+    /// keyframes positions at the time of the instance, see `SkeletonInstance`
+    cursor: Option<&'a AnimationCursor>
+}
+
+impl<'a> Bound<'a> {
+
+    /// the animation is `None` when sampling the setup pose of a bound animation
+    fn bone_timeline(&self, index: Option<usize>) -> Option<&'a skeleton::timelines::BoneTimeline> {
+        index.and_then(|i| self.animation.map(|anim| &anim.bones[i].1))
+    }
+
+    fn slot_timeline(&self, index: Option<usize>) -> Option<&'a skeleton::timelines::SlotTimeline> {
+        index.and_then(|i| self.animation.map(|anim| &anim.slots[i].1))
+    }
+
+    fn attachment(&self, key: &AttachmentKey) -> Option<&'a skeleton::Attachment> {
+        let skin = if key.default_skin { self.default_skin } else { self.skin };
+        skin.slots[key.index].1.get(key.attachment).map(|&(_, ref attachment)| attachment)
+    }
+
+    /// gets the keyframes cursor if it has been moved to the given time
+    fn cursor_at(&self, time: f32) -> Option<&'a AnimationCursor> {
+        self.cursor.and_then(|cursor| if cursor.time == time { Some(cursor) } else { None })
+    }
+
+    /// gets a bone local srt (setup pose + animation) at given time
+    fn get_bone_local_srt(&self, index: usize, time: f32) -> skeleton::SRT {
+
+        // starts with setup pose
+        let mut srt = self.skeleton.bones[index].srt.clone();
+
+        // add animation srt
+        let timeline = self.binding.bones[index];
+        let anim_srt = self.bone_timeline(timeline).map(|anim| match self.cursor_at(time) {
+            Some(cursor) => anim.srt_at(time, &cursor.bones[timeline.unwrap()]),
+            None => anim.srt(time)
+        });
+        if let Some(anim_srt) = anim_srt {
+            srt.position[0] += anim_srt.position[0];
+            srt.position[1] += anim_srt.position[1];
+            srt.rotation += anim_srt.rotation;
+            srt.scale[0] *= anim_srt.scale[0];
+            srt.scale[1] *= anim_srt.scale[1];
+        }
+        srt
+    }
+
+    /// gets all bones local srts (setup pose + animation) at given time
+    fn get_bones_local_srts(&self, time: f32) -> Vec<skeleton::SRT> {
+        (0..self.skeleton.bones.len()).map(|i| self.get_bone_local_srt(i, time)).collect()
+    }
+
+    /// writes all bones local srts into a buffer, replacing its content
+    fn write_bones_local_srts(&self, time: f32, locals: &mut Vec<skeleton::SRT>) {
+        locals.clear();
+        locals.extend((0..self.skeleton.bones.len()).map(|i| self.get_bone_local_srt(i, time)));
+    }
+
+    /// converts bones local srts into world srts by inheriting from their parent
+    fn get_bones_world_srts(&self, locals: Vec<skeleton::SRT>) -> Vec<skeleton::SRT> {
+        let mut srts = self.get_bones_skeleton_srts(locals);
+        for srt in &mut srts {
+            self.mirror(srt);
         }
         srts
     }
 
-    /// Interpolates animated slots at given time
-    pub fn interpolate<'b: 'a>(&'b self, time: f32) -> Option<Sprites<'b>> {
+    /// mirrors a world transform, rotation is reversed and scales negated
+    fn mirror(&self, srt: &mut skeleton::SRT) {
+        let y_down = self.skeleton.coordinates == skeleton::CoordinateSystem::YDown;
+        let flip = [self.binding.flip[0], self.binding.flip[1] != y_down];
+        for (axis, _) in flip.iter().enumerate().filter(|&(_, &flip)| flip) {
+            srt.position[axis] = -srt.position[axis];
+            srt.scale[axis] = -srt.scale[axis];
+            srt.rotation = -srt.rotation;
+            srt.sin = -srt.sin;
+        }
+    }
+
+    /// converts bones local srts into skeleton space srts, i.e. world srts before mirroring
+    fn get_bones_skeleton_srts(&self, locals: Vec<skeleton::SRT>) -> Vec<skeleton::SRT> {
+        let mut srts = Vec::with_capacity(locals.len());
+        self.write_bones_skeleton_srts(&locals, &mut skeleton::BoneArrays::new(), &mut srts);
+        srts
+    }
+
+    /// writes bones skeleton space srts into a buffer, using `bones` as scratch arrays
+    fn write_bones_skeleton_srts(&self, locals: &[skeleton::SRT], bones: &mut skeleton::BoneArrays,
+                                 srts: &mut Vec<skeleton::SRT>)
+    {
+        bones.load(locals);
+        bones.inherit(&self.skeleton.hierarchy);
+        bones.store(srts);
+
+        if let Some(ref root) = self.binding.root {
+            for srt in srts.iter_mut() {
+                mount(root, srt);
+            }
+        }
+    }
+
+    /// writes all bones world srts at given time into the buffers of a context
+    fn write_bones_srts(&self, time: f32, pose: Option<&Pose>, context: &mut EvalContext) {
+        self.write_bones_local_srts(time, &mut context.locals);
+        if let Some(pose) = pose {
+            pose.apply(&mut context.locals);
+        }
+        self.write_bones_skeleton_srts(&context.locals, &mut context.bones, &mut context.srts);
+        for srt in context.srts.iter_mut() {
+            self.mirror(srt);
+        }
+    }
 
-        if time > self.duration {
+    /// writes all bones world matrices into rows, see `SkinAnimation::write_bone_rows`
+    fn write_bones_rows(&self, time: f32, rows: &mut [[f32; 4]], context: &mut EvalContext) -> bool {
+        if time > self.binding.duration || rows.len() < 2 * self.skeleton.bones.len() {
+            return false;
+        }
+
+        self.write_bones_srts(time, None, context);
+        for (srt, rows) in context.srts.iter().zip(rows.chunks_mut(2)) {
+            srt_to_rows(srt, rows);
+        }
+        true
+    }
+
+    /// gets all slots colors at given time
+    fn get_slots_colors(&self, time: f32) -> Vec<[f32; 4]> {
+        let mut colors = Vec::with_capacity(self.skeleton.slots.len());
+        self.write_slots_colors(time, &mut colors);
+        colors
+    }
+
+    /// writes all slots colors at given time into a buffer, replacing its content
+    fn write_slots_colors(&self, time: f32, colors: &mut Vec<[f32; 4]>) {
+        let cursor = self.cursor_at(time);
+        colors.clear();
+        colors.extend(self.skeleton.slots.iter().zip(self.binding.slots.iter()).map(|(slot, &(_, timeline))|
+            self.slot_timeline(timeline).map(|anim| match cursor {
+                Some(cursor) => anim.interpolate_color_at(time, &cursor.slots[timeline.unwrap()]),
+                None => anim.interpolate_color(time)
+            }).unwrap_or(slot.color.clone())));
+    }
+
+    /// gets all bones world srts at given time, with optional procedural bones modifications
+    fn get_bones_srts(&self, time: f32, pose: Option<&Pose>) -> Option<Vec<skeleton::SRT>> {
+
+        if time > self.binding.duration {
             return None;
         }
 
-        let srts = self.get_bones_srts(time);
-        let iter = self.anim_slots.iter();
-        Some(Sprites {
-            iter: iter,
-            srts: srts,
-            time: time
+        let mut locals = self.get_bones_local_srts(time);
+        if let Some(pose) = pose {
+            pose.apply(&mut locals);
+        }
+        Some(self.get_bones_world_srts(locals))
+    }
+
+    /// gets the skeleton space srt of a bone at given time, see `get_bones_skeleton_srts`
+    fn bone_skeleton_srt(&self, index: usize, time: f32, pose: Option<&Pose>) -> Option<skeleton::SRT> {
+        if time > self.binding.duration {
+            return None;
+        }
+
+        let mut locals = self.get_bones_local_srts(time);
+        if let Some(pose) = pose {
+            pose.apply(&mut locals);
+        }
+        Some(self.get_bones_skeleton_srts(locals).swap_remove(index))
+    }
+
+    fn bone_world_srt(&self, name: &str, time: f32, pose: Option<&Pose>) -> Option<skeleton::SRT> {
+        self.skeleton.bones.iter().position(|b| b.name == name)
+            .and_then(|i| self.get_bones_srts(time, pose).map(|mut srts| srts.swap_remove(i)))
+    }
+
+    fn bones_world_srts(&self, time: f32, pose: Option<&Pose>) -> Option<Vec<(&'a str, skeleton::SRT)>> {
+        let bones = self.skeleton.bones.iter().map(|b| &*b.name);
+        self.get_bones_srts(time, pose).map(|srts| bones.zip(srts.into_iter()).collect())
+    }
+
+    /// interpolates sprites at given time, with optional procedural bones modifications
+    fn interpolate(self, time: f32, pose: Option<&Pose>) -> Option<Sprites<'a>> {
+        self.get_bones_srts(time, pose).map(|srts| {
+            let colors = self.get_slots_colors(time);
+            self.sprites(time, Cow::Owned(srts), Cow::Owned(colors))
         })
     }
 
-    /// Creates an iterator which iterates sprites at delta seconds interval
-    pub fn run<'b: 'a>(&'b self, delta: f32) -> AnimationIter<'b> {
-        AnimationIter {
-            skin_animation: &self,
-            time: 0f32,
-            delta: delta
+    /// interpolates sprites at given time into the buffers of a context
+    fn interpolate_in(self, time: f32, pose: Option<&Pose>, context: &'a mut EvalContext) -> Option<Sprites<'a>> {
+        if time > self.binding.duration {
+            return None;
+        }
+
+        self.write_bones_srts(time, pose, context);
+        self.write_slots_colors(time, &mut context.colors);
+
+        let context: &'a EvalContext = context;
+        Some(self.sprites(time, Cow::Borrowed(&context.srts), Cow::Borrowed(&context.colors)))
+    }
+
+    /// interpolates sprites at given time, crossfading the last `window` seconds of the
+    /// animation into its first frame so that looping does not pop
+    fn interpolate_looped(self, time: f32, window: f32) -> Option<Sprites<'a>> {
+        let start = self.binding.duration - window;
+        if window <= 0.0 || time <= start || time > self.binding.duration {
+            return self.interpolate(time, None);
+        }
+
+        let alpha = (time - start) / window;
+        let locals = self.get_bones_local_srts(time).iter().zip(self.get_bones_local_srts(0.0).iter())
+            .map(|(a, b)| a.interpolate(b, alpha)).collect();
+        let colors = self.get_slots_colors(time).iter().zip(self.get_slots_colors(0.0).iter())
+            .map(|(a, b)| a.interpolate(b, alpha)).collect();
+        let srts = self.get_bones_world_srts(locals);
+        Some(self.sprites(if alpha < 0.5 { time } else { 0.0 }, Cow::Owned(srts), Cow::Owned(colors)))
+    }
+
+    /// samples bones local srts, slots colors and attachments at given time
+    fn sample(self, time: f32) -> Option<SampledPose> {
+        if time > self.binding.duration {
+            return None;
+        }
+
+        let colors = self.get_slots_colors(time);
+        let attachments = {
+            let sprites = self.sprites(time, Cow::Borrowed(&[]), Cow::Borrowed(&[]));
+            self.binding.slots.iter().enumerate().map(|(i, &(ref skin_attach, timeline))| {
+                let (name, key) = sprites.slot_attachment(i, skin_attach, timeline);
+                (name.map(|n| n.to_owned()), key.clone())
+            }).collect()
+        };
+        Some(SampledPose {
+            bones: self.get_bones_local_srts(time),
+            colors: colors,
+            attachments: attachments
+        })
+    }
+
+    fn sprites(self, time: f32, srts: Cow<'a, [skeleton::SRT]>, colors: Cow<'a, [[f32; 4]]>) -> Sprites<'a> {
+        Sprites {
+            bound: self,
+            overrides: None,
+            tints: None,
+            skip_transparent: false,
+            mask: None,
+            attachments: None,
+            iter: self.binding.slots.iter().enumerate(),
+            srts: srts,
+            colors: colors,
+            time: time,
+            remaining: Cell::new(None)
+        }
+    }
+
+    fn events_between(self, from: f32, to: f32) -> Events<'a> {
+        let events: &[json::EventKeyframe] = self.animation.map(|anim| &*anim.events).unwrap_or(&[]);
+        let after = |time: f32| events.iter().position(|e| e.time > time).unwrap_or(events.len());
+        let (first, second) = if from <= to {
+            (&events[after(from)..after(to)], &events[..0])
+        } else {
+            (&events[after(from)..], &events[..after(to)])
+        };
+        Events {
+            iter: first.iter().chain(second.iter()),
+            definitions: &self.skeleton.events
         }
     }
 }
 
-/// Iterator over all sprites interpolated at a given time
-pub struct Sprites<'a> {
-    iter: Iter<'a, (&'a skeleton::Slot, AttachmentWrapper<'a>, Option<&'a skeleton::timelines::SlotTimeline>)>,
-    srts: Vec<skeleton::SRT>,
-    time: f32
+/// Skeleton data of an animation, borrowed or shared
+enum SkeletonRef<'a> {
+    Borrowed(&'a skeleton::Skeleton),
+    Shared(Arc<skeleton::Skeleton>)
 }
 
-impl<'a> Iterator for Sprites<'a> {
-    type Item = Sprite<'a>;
-    fn next<'b>(&'b mut self) -> Option<Sprite<'a>> {
-
-        while let Some(&(slot, ref skin_attach, anim)) = self.iter.next() {
-
-            // search animated attachment
-            let (name, skin_attach) = match *skin_attach {
-                AttachmentWrapper::Static(ref attach) => (None, attach),
-                AttachmentWrapper::Dynamic(ref attach, ref names) => {
-                    match anim.unwrap().interpolate_attachment(self.time) {
-                        Some(Some(name)) => {
-                            let attach = names.get(&*name).unwrap();
-                            (Some(name), attach)
-                        },
-                        Some(None) => (None, attach),
-                        None => (None, attach),
-                    }
-                }
-            };
+impl<'a> SkeletonRef<'a> {
+    fn get(&self) -> &skeleton::Skeleton {
+        match *self {
+            SkeletonRef::Borrowed(skeleton) => skeleton,
+            SkeletonRef::Shared(ref skeleton) => skeleton
+        }
+    }
+}
+
+/// Struct to handle animated skin and calculate sprites
+///
+/// The animation either borrows its skeleton (`Skeleton::get_animated_skin`) or shares it
+/// (`SkinAnimation::from_arc`). A `SkinAnimation<'static>` owning an `Arc` is `Send + Sync`, so
+/// it can be moved to other threads or stored in ECS components.
+pub struct SkinAnimation<'a> {
+    skeleton: SkeletonRef<'a>,
+    binding: Binding
+}
+
+/// Interpolated slot with attachment and color
+#[derive(Debug)]
+pub struct Sprite<'a> {
+    /// attachment name
+    pub attachment: &'a str,
+    /// slot name
+    pub slot: &'a str,
+    /// name of the bone of the slot
+    pub bone: &'a str,
+    /// index of the slot in the setup draw order, i.e. in `Sprites::slots`
+    ///
+    /// Draw order keys of animations are not applied, so this is also the index of the slot in
+    /// the skeleton.
+    pub draw_index: usize,
+    /// material, read from the attachment name suffix (e.g. "sword#glow")
+    pub material: Option<&'a str>,
+    /// color
+    pub color: [u8; 4],
+    /// color, normalized in `[0, 1]`
+    pub color_f32: [f32; 4],
+    /// srt
+    pub srt: skeleton::SRT,
+    /// world-space corners of the attachment quad: top-left, top-right, bottom-right, bottom-left
+    pub positions: [[f32; 2]; 4],
+    /// normalized texture coordinates of the corners, if an atlas has been bound
+    /// (see `Skeleton::bind_atlas`)
+    ///
+    /// Each corner has the coordinates of the same corner of the image, rotated regions are
+    /// already accounted for.
+    pub uvs: Option<[[f32; 2]; 4]>,
+    /// index of the atlas page of the region, if a set of atlases has been bound
+    /// (see `Skeleton::bind_atlas_set`)
+    pub page: Option<usize>
+}
+
+impl<'a> Sprite<'a> {
+
+    /// Gets the color with rgb multiplied by alpha, for premultiplied alpha blending
+    pub fn color_premultiplied(&self) -> [u8; 4] {
+        let c = self.color_f32;
+        [to_u8(c[0] * c[3]), to_u8(c[1] * c[3]), to_u8(c[2] * c[3]), self.color[3]]
+    }
+
+    /// Gets the corners of the quad with their texture coordinates, `None` if no atlas has been
+    /// bound
+    ///
+    /// The vertices can be drawn as is whether the region has been packed rotated or not, as
+    /// two triangles `[0, 1, 2]` and `[0, 2, 3]`.
+    pub fn vertices(&self) -> Option<[([f32; 2], [f32; 2]); 4]> {
+        self.uvs.map(|uvs| [(self.positions[0], uvs[0]), (self.positions[1], uvs[1]),
+                            (self.positions[2], uvs[2]), (self.positions[3], uvs[3])])
+    }
+}
+
+impl<'a> SkinAnimation<'a> {
+
+    /// Iterator<Item=Vec<CalculatedSlot>> where item are modified with timelines
+    pub fn new(skeleton: &'a skeleton::Skeleton, skin: &str, animation: Option<&str>)
+        -> Result<SkinAnimation<'a>, SkeletonError>
+    {
+        let binding = try!(Binding::new(skeleton, skin, animation));
+        Ok(SkinAnimation {
+            skeleton: SkeletonRef::Borrowed(skeleton),
+            binding: binding
+        })
+    }
+
+    /// Creates an animation sharing the skeleton data instead of borrowing it
+    pub fn from_arc(skeleton: Arc<skeleton::Skeleton>, skin: &str, animation: Option<&str>)
+        -> Result<SkinAnimation<'static>, SkeletonError>
+    {
+        let binding = try!(Binding::new(&skeleton, skin, animation));
+        Ok(SkinAnimation {
+            skeleton: SkeletonRef::Shared(skeleton),
+            binding: binding
+        })
+    }
+
+    /// Restricts the animation to the bones and slots of a mask
+    ///
+    /// Timelines of the bones and slots out of the mask are skipped, so that they keep their
+    /// setup pose.
+    pub fn with_mask(mut self, mask: &BoneMask) -> SkinAnimation<'a> {
+        self.binding.mask(mask);
+        self
+    }
+
+    /// Mirrors the animation horizontally and/or vertically
+    ///
+    /// Mirroring a single axis reverses the winding of sprites quads.
+    pub fn set_flip(&mut self, flip_x: bool, flip_y: bool) {
+        self.binding.flip = [flip_x, flip_y];
+    }
+
+    /// Sets the opacity multiplied into the alpha of all sprites, e.g. to fade the skeleton in or out
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.binding.opacity = opacity;
+    }
+
+    /// Gets the opacity multiplied into the alpha of all sprites
+    pub fn get_opacity(&self) -> f32 {
+        self.binding.opacity
+    }
+
+    /// Gets whether the animation is mirrored horizontally and vertically
+    pub fn get_flip(&self) -> (bool, bool) {
+        (self.binding.flip[0], self.binding.flip[1])
+    }
+
+    fn bound(&self) -> Bound {
+        self.binding.bind(self.skeleton.get())
+    }
+
+    /// Gets duration of the animation, i.e. the time of its last key (including events and
+    /// draw order keys)
+    pub fn get_duration(&self) -> f32 {
+        self.binding.duration
+    }
+
+    /// Gets the number of slots, i.e. the number of items of `Sprites::slots`
+    pub fn get_slots_count(&self) -> usize {
+        self.skeleton.get().slots.len()
+    }
+
+    /// Gets the skeleton the animation has been created from
+    pub fn get_skeleton(&self) -> &skeleton::Skeleton {
+        self.skeleton.get()
+    }
+
+    /// Interpolates animated slots at given time
+    pub fn interpolate<'b>(&'b self, time: f32) -> Option<Sprites<'b>> {
+        self.bound().interpolate(time, None)
+    }
+
+    /// Interpolates animated slots at given time, in the buffers of a context instead of
+    /// allocating them
+    pub fn interpolate_in<'b>(&'b self, time: f32, context: &'b mut EvalContext) -> Option<Sprites<'b>> {
+        self.bound().interpolate_in(time, None, context)
+    }
+
+    /// Gets the sprites of the setup pose of the skin, ignoring the animation
+    ///
+    /// Slots show their setup attachment and color, in setup draw order.
+    pub fn setup_pose<'b>(&'b self) -> Sprites<'b> {
+        let bound = Bound { animation: None, ..self.bound() };
+        bound.interpolate(0.0, None).expect("setup pose is always in the animation")
+    }
+
+    /// Interpolates animated slots at given time, clamped to the animation
+    ///
+    /// Times past the duration hold the last frame, negative times the first one.
+    pub fn interpolate_clamped<'b>(&'b self, time: f32) -> Sprites<'b> {
+        let time = time.max(0.0).min(self.get_duration());
+        self.interpolate(time).expect("clamped time is always in the animation")
+    }
+
+    /// Interpolates animated slots at given time, crossfading the last `window` seconds of the
+    /// animation into its first frame
+    ///
+    /// Looping an animation whose first and last keys differ then wraps without popping.
+    pub fn interpolate_looped<'b>(&'b self, time: f32, window: f32) -> Option<Sprites<'b>> {
+        self.bound().interpolate_looped(time, window)
+    }
+
+    /// Interpolates animated slots at given time, with procedural bones modifications
+    pub fn interpolate_with_pose<'b>(&'b self, time: f32, pose: &Pose) -> Option<Sprites<'b>> {
+        self.bound().interpolate(time, Some(pose))
+    }
+
+    /// Gets the world srt of a bone at given time, to attach external objects to it
+    ///
+    /// Returns `None` if the bone does not exist or if time is out of the animation.
+    pub fn bone_world_srt(&self, name: &str, time: f32) -> Option<skeleton::SRT> {
+        self.bound().bone_world_srt(name, time, None)
+    }
+
+    /// Gets the names and world srts of all bones at given time
+    pub fn bones_world_srts(&self, time: f32) -> Option<Vec<(&str, skeleton::SRT)>> {
+        let bones = self.skeleton.get().bones.iter().map(|b| &*b.name);
+        self.bound().get_bones_srts(time, None).map(|srts| bones.zip(srts.into_iter()).collect())
+    }
+
+    /// Gets the world matrices of all bones at given time, in skeleton order, for GPU skinning
+    ///
+    /// Bones are indexed as in `Skeleton::get_bones_names`, see `SRT::to_matrix3x2` for the layout.
+    pub fn bone_matrices(&self, time: f32) -> Option<Vec<[[f32; 2]; 3]>> {
+        self.bound().get_bones_srts(time, None).map(|srts| srts.iter().map(|srt| srt.to_matrix3x2()).collect())
+    }
+
+    /// Gets the world matrices of all bones at given time as 4x4 matrices, see `bone_matrices`
+    pub fn bone_matrices4(&self, time: f32) -> Option<Vec<[[f32; 4]; 4]>> {
+        self.bone_matrices(time).map(|matrices| matrices.iter().map(|m| to_matrix4(m)).collect())
+    }
+
+    /// Writes the world matrices of all bones at given time into packed rows, without allocating
+    ///
+    /// Each bone, in skeleton order, takes two rows: `[a, c, tx, 0]` and `[b, d, ty, 0]` where
+    /// `[a, b]`, `[c, d]` and `[tx, ty]` are the columns of `SRT::to_matrix3x2`, so that `rows`
+    /// can be copied as is into a uniform or storage buffer. `rows` must hold at least twice as
+    /// many rows as bones.
+    ///
+    /// Bones srts are computed in the buffers of `context`. Returns the number of rows written,
+    /// `None` if time is out of the animation or if `rows` is too small.
+    pub fn write_bone_rows(&self, time: f32, rows: &mut [[f32; 4]], context: &mut EvalContext) -> Option<usize> {
+        if self.bound().write_bones_rows(time, rows, context) {
+            Some(2 * self.skeleton.get().bones.len())
+        } else {
+            None
+        }
+    }
+
+    /// Gets the axis-aligned bounding box `[min, max]` of all visible attachments at given time
+    ///
+    /// Returns `None` if time is out of the animation or if nothing is visible.
+    pub fn bounds(&self, time: f32) -> Option<[[f32; 2]; 2]> {
+        self.interpolate(time).and_then(|sprites| sprites.bounds())
+    }
+
+    /// Gets the world polygons of all visible bounding boxes at given time, in draw order
+    ///
+    /// Regions quads are included if `regions` is set. Polygons may be concave, e.g. to
+    /// synchronize the colliders of a physics engine with the animation.
+    pub fn collision_polygons(&self, time: f32, regions: bool) -> Option<Vec<Vec<[f32; 2]>>> {
+        self.interpolate(time).map(|sprites| sprites.polygons(regions))
+    }
 
-            // nothing to show if there is no attachment
-            if let Some(ref skin_attach) = *skin_attach {
+    /// Finds the topmost slot whose attachment contains the world `point` at given time
+    ///
+    /// Returns the names of the slot and of the attachment hit, bounding boxes are tested
+    /// against their polygon and regions against their quad.
+    pub fn hit_test<'b>(&'b self, point: [f32; 2], time: f32) -> Option<(&'b str, &'b str)> {
+        self.interpolate(time).and_then(|sprites| sprites.hit_test(point))
+    }
 
-                // color
-                let color = anim.map(|anim| anim.interpolate_color(self.time))
-                            .unwrap_or(slot.color.clone());
+    /// Samples bones, slots colors and attachments at given time
+    ///
+    /// Returns `None` if time is out of the animation.
+    pub fn sample(&self, time: f32) -> Option<SampledPose> {
+        self.bound().sample(time)
+    }
 
-                // attachment name
-                let attach_name = name.or(skin_attach.name.as_ref()
-                                      .or(slot.attachment.as_ref()).map(|n| &**n))
-                                  .expect("no attachment name provided");
+    /// Samples this animation at `time` on top of `base`
+    ///
+    /// Only the bones and slots which have timelines in the animation are replaced, the other
+    /// ones keep their value in `base`. Returns `None` if time is out of the animation.
+    pub fn sample_over(&self, time: f32, base: &SampledPose) -> Option<SampledPose> {
+        self.sample(time).map(|sample| {
+            let mut pose = base.clone();
+            for (i, timeline) in self.binding.bones.iter().enumerate() {
+                if timeline.is_some() {
+                    pose.bones[i] = sample.bones[i].clone();
+                }
+            }
+            for (i, &(_, timeline)) in self.binding.slots.iter().enumerate() {
+                if timeline.is_some() {
+                    pose.colors[i] = sample.colors[i];
+                    pose.attachments[i] = sample.attachments[i].clone();
+                }
+            }
+            pose
+        })
+    }
 
-                return Some(Sprite {
-                    attachment: attach_name,
-                    srt: self.srts[slot.bone_index].clone(),
-                    color: color
-                })
+    /// Samples this animation at `time`, then layers other animations on top of it
+    ///
+    /// Each layer is an animation sampled at its own time, replacing the bones and slots of its
+    /// mask. When masks overlap, latter layers take precedence. All animations must have been
+    /// created from the same skeleton and skin. Returns `None` if any time is out of its animation.
+    pub fn sample_layers(&self, time: f32, layers: &[(&SkinAnimation, &BoneMask, f32)])
+        -> Option<SampledPose>
+    {
+        let mut pose = match self.sample(time) {
+            Some(pose) => pose,
+            None => return None
+        };
+        for &(animation, mask, time) in layers {
+            match animation.sample(time) {
+                Some(layer) => pose.layer(&layer, mask),
+                None => return None
             }
         }
+        Some(pose)
+    }
 
-        // end of iter
-        None
+    /// Mirrors a sampled pose across the Y axis, swapping the bones of `mapping`
+    ///
+    /// Each bone of `mapping` (e.g. "left arm" to "right arm") takes the mirrored transform of
+    /// the other one, so that an animation authored for one side drives the other side.
+    /// Slots and attachments are left untouched.
+    pub fn mirror_pose(&self, pose: &SampledPose, mapping: &HashMap<String, String>)
+        -> Result<SampledPose, SkeletonError>
+    {
+        // as `Bound::mirror`, world transforms have their rotation reversed and their x scale
+        // negated: children inherit the mirrored x scale, only their rotation is reversed
+        let mut mirrored = pose.clone();
+        for (srt, bone) in mirrored.bones.iter_mut().zip(self.skeleton.get().bones.iter()) {
+            if bone.parent_index.is_none() {
+                srt.position[0] = -srt.position[0];
+            }
+            if bone.parent_index.is_none() || !bone.inherit_scale {
+                srt.scale[0] = -srt.scale[0];
+            }
+            srt.rotation = -srt.rotation;
+            srt.sin = -srt.sin;
+        }
+        let symmetric = mirrored.clone();
+        for (a, b) in mapping {
+            let a = try!(skeleton::bone_index(a, &self.skeleton.get().bones));
+            let b = try!(skeleton::bone_index(b, &self.skeleton.get().bones));
+            mirrored.bones[a] = symmetric.bones[b].clone();
+            mirrored.bones[b] = symmetric.bones[a].clone();
+        }
+        Ok(mirrored)
     }
-}
 
-/// Iterator over a constant period
-#[derive(Clone)]
-pub struct AnimationIter<'a> {
-    skin_animation: &'a SkinAnimation<'a>,
-    time: f32,
-    delta: f32
-}
+    /// Interpolates the sprites of a sampled pose
+    ///
+    /// The pose must have been sampled from an animation of the same skeleton and skin.
+    pub fn pose_sprites<'b>(&'b self, pose: &'b SampledPose) -> Sprites<'b> {
+        let bound = self.bound();
+        let srts = bound.get_bones_world_srts(pose.bones.clone());
+        let mut sprites = bound.sprites(0.0, Cow::Owned(srts), Cow::Borrowed(&pose.colors));
+        sprites.attachments = Some(&pose.attachments);
+        sprites
+    }
 
-impl<'a> Iterator for AnimationIter<'a> {
-    type Item = Sprites<'a>;
-    fn next(&mut self) -> Option<Sprites<'a>> {
-        let result = self.skin_animation.interpolate(self.time);
-        self.time += self.delta;
-        result
+    /// Gets the names and world srts of all bones of a sampled pose, see `pose_sprites`
+    pub fn pose_world_srts(&self, pose: &SampledPose) -> Vec<(&str, skeleton::SRT)> {
+        let bones = self.skeleton.get().bones.iter().map(|b| &*b.name);
+        bones.zip(self.bound().get_bones_world_srts(pose.bones.clone())).collect()
+    }
+
+    /// Freezes the animation at given time (clamped to the animation), see `FrozenPose`
+    pub fn freeze(self, time: f32) -> FrozenPose<'a> {
+        FrozenPose::new(self, time)
+    }
+
+    /// Pre-samples the animation at `fps` frames per second, see `BakedAnimation`
+    ///
+    /// Panics if `fps` is not strictly positive and finite.
+    pub fn bake(self, fps: f32) -> BakedAnimation<'a> {
+        BakedAnimation::new(self, fps)
+    }
+
+    /// Blends this animation at `time_a` with `other` at `time_b`
+    ///
+    /// `alpha` is the weight of `other`: 0 only shows this animation, 1 only shows `other`.
+    /// Bones srts and slots colors are interpolated, attachments are taken from the animation
+    /// with the highest weight.
+    ///
+    /// Both animations must have been created from the same skeleton.
+    pub fn mix<'b>(&'b self, other: &'b SkinAnimation<'a>, alpha: f32, time_a: f32, time_b: f32)
+        -> Option<Sprites<'b>>
+    {
+        if time_a > self.get_duration() || time_b > other.get_duration() {
+            return None;
+        }
+
+        let (bound, other) = (self.bound(), other.bound());
+        let locals = bound.get_bones_local_srts(time_a).iter()
+            .zip(other.get_bones_local_srts(time_b).iter())
+            .map(|(a, b)| a.interpolate(b, alpha)).collect();
+        let srts = bound.get_bones_world_srts(locals);
+
+        let colors = bound.get_slots_colors(time_a).iter()
+            .zip(other.get_slots_colors(time_b).iter())
+            .map(|(a, b)| a.interpolate(b, alpha)).collect();
+
+        Some(if alpha < 0.5 {
+            bound.sprites(time_a, Cow::Owned(srts), Cow::Owned(colors))
+        } else {
+            other.sprites(time_b, Cow::Owned(srts), Cow::Owned(colors))
+        })
+    }
+
+    /// Gets the events triggered in `(from, to]`
+    ///
+    /// If `to` is lower than `from`, the animation is considered to have looped and the events
+    /// in `(from, duration]` then in `[0, to]` are returned.
+    pub fn events_between<'b>(&'b self, from: f32, to: f32) -> Events<'b> {
+        self.bound().events_between(from, to)
+    }
+
+    /// Creates an iterator over the sprites which have changed between `previous` and `current`
+    /// times
+    ///
+    /// Transforms are compared up to `epsilon`, colors and attachments must match exactly.
+    /// Returns `None` if any of the times is out of the animation.
+    pub fn delta<'b>(&'b self, previous: f32, current: f32, epsilon: f32) -> Option<SpritesDelta<'b>> {
+        match (self.interpolate(previous), self.interpolate(current)) {
+            (Some(previous), Some(current)) => Some(SpritesDelta {
+                previous: previous,
+                current: current,
+                slot_index: 0,
+                epsilon: epsilon
+            }),
+            _ => None
+        }
+    }
+
+    /// Creates an iterator which iterates sprites at delta seconds interval
+    pub fn run<'b>(&'b self, delta: f32) -> AnimationIter<'b> {
+        AnimationIter {
+            skin_animation: &self,
+            time: 0f32,
+            delta: delta,
+            end: AnimationEnd::Stop,
+            crossfade: 0f32
+        }
+    }
+}
+
+/// Bones and slots of a skin animation sampled at a given time
+///
+/// Poses can be modified or blended together, e.g. to implement custom transitions, then drawn
+/// with `SkinAnimation::pose_sprites`.
+#[derive(Clone)]
+pub struct SampledPose {
+    /// local srts of the bones
+    bones: Vec<skeleton::SRT>,
+    /// colors of the slots
+    colors: Vec<[f32; 4]>,
+    /// attachments of the slots, along with their name if it differs from the skin one
+    attachments: Vec<(Option<String>, Option<AttachmentKey>)>
+}
+
+impl SampledPose {
+
+    /// Gets the local srts of the bones, in skeleton order
+    pub fn get_bones(&self) -> &[skeleton::SRT] {
+        &self.bones
+    }
+
+    /// Gets the local srts of the bones, to edit them
+    pub fn get_bones_mut(&mut self) -> &mut [skeleton::SRT] {
+        &mut self.bones
+    }
+
+    /// Gets the colors of the slots, in skeleton order
+    pub fn get_colors(&self) -> &[[f32; 4]] {
+        &self.colors
+    }
+
+    /// Gets the colors of the slots, to edit them
+    pub fn get_colors_mut(&mut self) -> &mut [[f32; 4]] {
+        &mut self.colors
+    }
+
+    /// Replaces the bones and slots of the mask by the ones of `other`
+    pub fn layer(&mut self, other: &SampledPose, mask: &BoneMask) {
+        for (i, (bone, other)) in self.bones.iter_mut().zip(other.bones.iter()).enumerate() {
+            if mask.contains_bone(i) {
+                *bone = other.clone();
+            }
+        }
+        let slots = self.colors.iter_mut().zip(self.attachments.iter_mut())
+            .zip(other.colors.iter().zip(other.attachments.iter()));
+        for (i, ((color, attach), (other_color, other_attach))) in slots.enumerate() {
+            if mask.contains_slot(i) {
+                *color = *other_color;
+                *attach = other_attach.clone();
+            }
+        }
+    }
+
+    /// Blends this pose with `other`
+    ///
+    /// `alpha` is the weight of `other`: 0 returns this pose, 1 returns `other`.
+    /// Bones srts and slots colors are interpolated, attachments are taken from the pose
+    /// with the highest weight. Both poses must come from the same skeleton and skin.
+    pub fn blend(&self, other: &SampledPose, alpha: f32) -> SampledPose {
+        SampledPose {
+            bones: self.bones.iter().zip(other.bones.iter())
+                .map(|(a, b)| a.interpolate(b, alpha)).collect(),
+            colors: self.colors.iter().zip(other.colors.iter())
+                .map(|(a, b)| a.interpolate(b, alpha)).collect(),
+            attachments: if alpha < 0.5 { self.attachments.clone() } else { other.attachments.clone() }
+        }
+    }
+}
+
+/// Animation pre-sampled at a fixed rate
+///
+/// Bones world srts and slots colors are linearly interpolated between the two closest frames,
+/// attachments are taken from the closest frame. This is cheaper than interpolating timelines
+/// but less accurate for curved keyframes.
+pub struct BakedAnimation<'a> {
+    animation: SkinAnimation<'a>,
+    fps: f32,
+    /// number of frames
+    frames: usize,
+    /// bones world srts, at `frame * bones + bone`
+    srts: Vec<skeleton::SRT>,
+    /// slots colors, at `frame * slots + slot`
+    colors: Vec<[f32; 4]>,
+    /// slots attachments, at `frame * slots + slot`
+    attachments: Vec<(Option<String>, Option<AttachmentKey>)>
+}
+
+impl<'a> BakedAnimation<'a> {
+
+    /// Samples an animation at `fps` frames per second
+    ///
+    /// Panics if `fps` is not strictly positive and finite.
+    pub fn new(animation: SkinAnimation<'a>, fps: f32) -> BakedAnimation<'a> {
+        assert!(fps > 0.0 && fps.is_finite(), "baking rate must be positive, got {}", fps);
+
+        let duration = animation.get_duration();
+        let frames = (duration * fps).ceil() as usize + 1;
+        let (bones, slots) = (animation.get_skeleton().bones.len(), animation.get_slots_count());
+        let mut srts = Vec::with_capacity(frames * bones);
+        let mut colors = Vec::with_capacity(frames * slots);
+        let mut attachments = Vec::with_capacity(frames * slots);
+        {
+            let bound = animation.bound();
+            for i in 0..frames {
+                let pose = bound.sample((i as f32 / fps).min(duration)).unwrap();
+                srts.extend(bound.get_bones_world_srts(pose.bones));
+                colors.extend(pose.colors);
+                attachments.extend(pose.attachments);
+            }
+        }
+        BakedAnimation {
+            animation: animation,
+            fps: fps,
+            frames: frames,
+            srts: srts,
+            colors: colors,
+            attachments: attachments
+        }
+    }
+
+    /// Gets duration of the animation
+    pub fn get_duration(&self) -> f32 {
+        self.animation.get_duration()
+    }
+
+    /// Interpolates sprites at given time, `None` if time is out of the animation
+    pub fn interpolate<'b>(&'b self, time: f32) -> Option<Sprites<'b>> {
+        self.frames_at(time).map(|frames| {
+            let (mut srts, mut colors) = (Vec::new(), Vec::new());
+            self.lerp(frames, &mut srts, &mut colors);
+            self.sprites(time, frames, Cow::Owned(srts), Cow::Owned(colors))
+        })
+    }
+
+    /// Interpolates sprites at given time in the buffers of a context, see
+    /// `SkinAnimation::interpolate_in`
+    pub fn interpolate_in<'b>(&'b self, time: f32, context: &'b mut EvalContext) -> Option<Sprites<'b>> {
+        self.frames_at(time).map(move |frames| {
+            self.lerp(frames, &mut context.srts, &mut context.colors);
+            let context: &'b EvalContext = context;
+            self.sprites(time, frames, Cow::Borrowed(&context.srts), Cow::Borrowed(&context.colors))
+        })
+    }
+
+    /// gets the frames around a time and the weight of the latter one
+    fn frames_at(&self, time: f32) -> Option<(usize, usize, f32)> {
+        let duration = self.get_duration();
+        if time < 0.0 || time > duration {
+            return None;
+        }
+
+        let last = self.frames - 1;
+        let i = ((time * self.fps) as usize).min(last);
+        let j = (i + 1).min(last);
+        let (ti, tj) = (i as f32 / self.fps, (j as f32 / self.fps).min(duration));
+        Some((i, j, if tj > ti { (time - ti) / (tj - ti) } else { 0.0 }))
+    }
+
+    /// writes the bones srts and slots colors interpolated between two frames into buffers
+    fn lerp(&self, (i, j, alpha): (usize, usize, f32), srts: &mut Vec<skeleton::SRT>, colors: &mut Vec<[f32; 4]>) {
+        let (bones, slots) = (self.srts.len() / self.frames, self.colors.len() / self.frames);
+        srts.clear();
+        srts.extend(self.srts[i * bones..(i + 1) * bones].iter().zip(self.srts[j * bones..(j + 1) * bones].iter())
+            .map(|(a, b)| a.interpolate(b, alpha)));
+        colors.clear();
+        colors.extend(self.colors[i * slots..(i + 1) * slots].iter().zip(self.colors[j * slots..(j + 1) * slots].iter())
+            .map(|(a, b)| a.interpolate(b, alpha)));
+    }
+
+    /// creates the sprites of interpolated srts and colors, with the attachments of the closest frame
+    fn sprites<'b>(&'b self, time: f32, (i, j, alpha): (usize, usize, f32),
+                   srts: Cow<'b, [skeleton::SRT]>, colors: Cow<'b, [[f32; 4]]>) -> Sprites<'b>
+    {
+        let slots = self.attachments.len() / self.frames;
+        let frame = if alpha < 0.5 { i } else { j };
+        let mut sprites = self.animation.bound().sprites(time, srts, colors);
+        sprites.attachments = Some(&self.attachments[frame * slots..(frame + 1) * slots]);
+        sprites
+    }
+}
+
+/// Animation frozen at a given time, e.g. for corpses or statues
+///
+/// Bones world srts, slots colors and attachments are computed once, drawing does not evaluate
+/// any timeline.
+pub struct FrozenPose<'a> {
+    animation: SkinAnimation<'a>,
+    time: f32,
+    srts: Vec<skeleton::SRT>,
+    pose: SampledPose
+}
+
+impl<'a> FrozenPose<'a> {
+
+    /// Freezes an animation at given time, clamped to the animation
+    pub fn new(animation: SkinAnimation<'a>, time: f32) -> FrozenPose<'a> {
+        let time = time.max(0.0).min(animation.get_duration());
+        let (srts, pose) = {
+            let bound = animation.bound();
+            let pose = bound.sample(time).expect("clamped time is always in the animation");
+            (bound.get_bones_world_srts(pose.bones.clone()), pose)
+        };
+        FrozenPose {
+            animation: animation,
+            time: time,
+            srts: srts,
+            pose: pose
+        }
+    }
+
+    /// Gets the time the animation has been frozen at
+    pub fn get_time(&self) -> f32 {
+        self.time
+    }
+
+    /// Gets the frozen pose, in local space
+    pub fn get_pose(&self) -> &SampledPose {
+        &self.pose
+    }
+
+    /// Gets the sprites of the frozen pose
+    pub fn sprites<'b>(&'b self) -> Sprites<'b> {
+        let colors = Cow::Borrowed(&*self.pose.colors);
+        let mut sprites = self.animation.bound().sprites(self.time, Cow::Borrowed(&self.srts), colors);
+        sprites.attachments = Some(&self.pose.attachments);
+        sprites
+    }
+}
+
+/// Scratch buffers reused between interpolations, see `SkinAnimation::interpolate_in`
+///
+/// Interpolating allocates the bones srts and slots colors on each call. A context created once,
+/// e.g. per thread, keeps them between frames: the returned sprites borrow the context until
+/// they are dropped. A context can be used with any skeleton.
+#[derive(Default)]
+pub struct EvalContext {
+    /// bones local srts
+    locals: Vec<skeleton::SRT>,
+    /// bones srts while inheriting from their parents
+    bones: skeleton::BoneArrays,
+    /// bones world srts
+    srts: Vec<skeleton::SRT>,
+    /// slots colors
+    colors: Vec<[f32; 4]>
+}
+
+impl EvalContext {
+
+    /// Creates a context with empty buffers, they grow to the size of the largest skeleton
+    pub fn new() -> EvalContext {
+        EvalContext::default()
+    }
+}
+
+/// Iterator over all sprites interpolated at a given time
+pub struct Sprites<'a> {
+    bound: Bound<'a>,
+    overrides: Option<&'a HashMap<usize, Option<AttachmentKey>>>,
+    /// colors multiplied into the slots colors, by slot index
+    tints: Option<&'a HashMap<usize, [f32; 4]>>,
+    /// whether slots with a zero alpha have nothing to show
+    skip_transparent: bool,
+    /// slots out of the mask have nothing to show
+    mask: Option<&'a BoneMask>,
+    attachments: Option<&'a [(Option<String>, Option<AttachmentKey>)]>,
+    iter: Enumerate<Iter<'a, (AttachmentWrapper, Option<usize>)>>,
+    srts: Cow<'a, [skeleton::SRT]>,
+    /// slots colors, by slot index
+    colors: Cow<'a, [[f32; 4]]>,
+    time: f32,
+    /// number of remaining sprites, counted on the first `size_hint`
+    remaining: Cell<Option<usize>>
+}
+
+impl<'a> Sprites<'a> {
+
+    /// Converts into an iterator over all slots, in setup draw order
+    pub fn slots(self) -> SlotSprites<'a> {
+        SlotSprites {
+            sprites: self
+        }
+    }
+
+    /// Omits the sprites whose alpha is zero, e.g. parts hidden by keying their color
+    ///
+    /// With `slots`, their slots are `None`.
+    pub fn skip_transparent(mut self) -> Sprites<'a> {
+        self.skip_transparent = true;
+        self.remaining.set(None);
+        self
+    }
+
+    /// Omits the sprites of the slots out of a mask, e.g. to draw a shadow pass with only the
+    /// "shadow" slot (see `BoneMask::from_slots`)
+    ///
+    /// With `slots`, their slots are `None`.
+    pub fn filter_slots(mut self, mask: &'a BoneMask) -> Sprites<'a> {
+        self.mask = Some(mask);
+        self.remaining.set(None);
+        self
+    }
+
+    /// Checks whether a slot is hidden by `skip_transparent` or `filter_slots`
+    fn is_hidden(&self, i: usize, color: [f32; 4]) -> bool {
+        (self.skip_transparent && color[3] <= 0.0) || self.mask.map(|m| !m.contains_slot(i)).unwrap_or(false)
+    }
+
+    /// Applies the tint of a slot and the opacity to its interpolated color
+    fn slot_color(&self, i: usize, color: [f32; 4]) -> [f32; 4] {
+        let mut color = match self.tints.and_then(|t| t.get(&i)) {
+            Some(tint) => [color[0] * tint[0], color[1] * tint[1], color[2] * tint[2], color[3] * tint[3]],
+            None => color
+        };
+        color[3] *= self.bound.binding.opacity;
+        color
+    }
+
+    /// Interpolates next slot, `Some(None)` if the slot has nothing to show
+    fn next_slot(&mut self) -> Option<Option<Sprite<'a>>> {
+        self.next_slot_attachment().map(|s| s.map(|(sprite, _)| sprite))
+    }
+
+    /// Interpolates next slot, along with its attachment
+    fn next_slot_attachment(&mut self) -> Option<Option<(Sprite<'a>, &'a skeleton::Attachment)>> {
+
+        let bound = self.bound;
+        match self.iter.next() {
+            Some((i, &(ref skin_attach, timeline))) => {
+                let (slot, color) = (&bound.skeleton.slots[i], self.colors[i]);
+                let (name, key) = self.slot_attachment(i, skin_attach, timeline);
+                let skin_attach = key.as_ref().and_then(|key| bound.attachment(key));
+                let color = self.slot_color(i, color);
+                if self.is_hidden(i, color) {
+                    return Some(None);
+                }
+                let sprite = self.sprite(i, slot, name, skin_attach, color);
+                if let (Some(remaining), true) = (self.remaining.get(), sprite.is_some()) {
+                    self.remaining.set(Some(remaining - 1));
+                }
+                Some(sprite)
+            },
+            None => None
+        }
+    }
+
+    /// Searches the sampled, overridden, animated or skin attachment of a slot, along with its
+    /// name if it differs from the skin one
+    fn slot_attachment(&self, i: usize, skin_attach: &'a AttachmentWrapper, timeline: Option<usize>)
+        -> (Option<&'a str>, &'a Option<AttachmentKey>)
+    {
+        if let Some(&(ref name, ref key)) = self.attachments.map(|a| &a[i]) {
+            return (name.as_ref().map(|n| &**n), key);
+        }
+
+        let bound = self.bound;
+        let (name, skin_attach) = match (self.overrides.and_then(|o| o.get(&i)), skin_attach) {
+            (Some(attach), _) => (attach.as_ref().map(|a| &*a.name), attach),
+            (None, &AttachmentWrapper::Static(ref attach)) => (None, attach),
+            (None, &AttachmentWrapper::Dynamic(ref attach, ref keys)) => {
+                let key = bound.slot_timeline(timeline).and_then(|t| match bound.cursor_at(self.time) {
+                    Some(cursor) => t.interpolate_attachment_key_at(&cursor.slots[timeline.unwrap()]),
+                    None => t.interpolate_attachment_key(self.time)
+                });
+                match key {
+                    Some((k, name)) => (name, &keys[k]),
+                    None => (None, attach),
+                }
+            }
+        };
+        (name, skin_attach)
+    }
+
+    /// Creates the sprite of a slot, `None` if there is no attachment to show
+    fn sprite(&self, draw_index: usize, slot: &'a skeleton::Slot, name: Option<&'a str>,
+              skin_attach: Option<&'a skeleton::Attachment>, color: [f32; 4])
+        -> Option<(Sprite<'a>, &'a skeleton::Attachment)>
+    {
+        // nothing to show if there is no attachment
+        skin_attach.map(|skin_attach| {
+
+            // attachment name
+            let attach_name = name.or(skin_attach.name.as_ref()
+                                  .or(slot.attachment.as_ref()).map(|n| &**n))
+                              .expect("no attachment name provided");
+
+            let srt = &self.srts[slot.bone_index];
+            let mut positions = [[0f32; 2]; 4];
+            for (p, position) in positions.iter_mut().zip(skin_attach.positions.iter()) {
+                *p = srt.transform(*position);
+            }
+
+            (Sprite {
+                attachment: attach_name,
+                slot: &slot.name,
+                bone: &self.bound.skeleton.bones[slot.bone_index].name,
+                draw_index: draw_index,
+                material: skin_attach.material.as_ref().map(|m| &**m),
+                srt: srt.clone(),
+                color: [to_u8(color[0]), to_u8(color[1]), to_u8(color[2]), to_u8(color[3])],
+                color_f32: color,
+                positions: positions,
+                uvs: skin_attach.uvs,
+                page: skin_attach.page
+            }, skin_attach)
+        })
+    }
+
+    /// Gets the axis-aligned bounding box `[min, max]` of all remaining sprites
+    fn bounds(mut self) -> Option<[[f32; 2]; 2]> {
+        let mut bounds: Option<[[f32; 2]; 2]> = None;
+        while let Some(sprite) = self.next_slot_attachment() {
+            if let Some((sprite, attach)) = sprite {
+                for p in attach.positions.iter().map(|&p| sprite.srt.transform(p)) {
+                    bounds = Some(match bounds {
+                        Some([min, max]) => [[min[0].min(p[0]), min[1].min(p[1])],
+                                             [max[0].max(p[0]), max[1].max(p[1])]],
+                        None => [p, p]
+                    });
+                }
+            }
+        }
+        bounds
+    }
+
+    /// Gets the world polygons of all remaining bounding boxes, and of regions quads if `regions`
+    fn polygons(mut self, regions: bool) -> Vec<Vec<[f32; 2]>> {
+        let mut polygons = Vec::new();
+        while let Some(sprite) = self.next_slot_attachment() {
+            if let Some((sprite, attach)) = sprite {
+                if regions || attach.is_bounding_box() {
+                    polygons.push(attach.polygon().iter().map(|&p| sprite.srt.transform(p)).collect());
+                }
+            }
+        }
+        polygons
+    }
+
+    /// Finds the topmost remaining slot whose attachment contains `point`
+    fn hit_test(mut self, point: [f32; 2]) -> Option<(&'a str, &'a str)> {
+        let mut hit = None;
+        while let Some(sprite) = self.next_slot_attachment() {
+            if let Some((sprite, attach)) = sprite {
+                let polygon: Vec<_> = attach.polygon().iter().map(|&p| sprite.srt.transform(p)).collect();
+                if polygon_contains(&polygon, point) {
+                    hit = Some((sprite.slot, sprite.attachment));
+                }
+            }
+        }
+        hit
+    }
+}
+
+/// Checks whether `point` is inside `polygon` (even-odd rule)
+fn polygon_contains(polygon: &[[f32; 2]], point: [f32; 2]) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len().wrapping_sub(1);
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[j]);
+        if (a[1] > point[1]) != (b[1] > point[1]) &&
+            point[0] < (b[0] - a[0]) * (point[1] - a[1]) / (b[1] - a[1]) + a[0]
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+impl<'a> Iterator for Sprites<'a> {
+    type Item = Sprite<'a>;
+    fn next(&mut self) -> Option<Sprite<'a>> {
+        while let Some(sprite) = self.next_slot() {
+            if sprite.is_some() {
+                return sprite;
+            }
+        }
+
+        // end of iter
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let visible = self.remaining.get().unwrap_or_else(|| {
+            let visible = self.iter.clone().filter(|&(i, &(ref skin_attach, timeline))| {
+                !self.is_hidden(i, self.slot_color(i, self.colors[i])) &&
+                    self.slot_attachment(i, skin_attach, timeline).1.as_ref()
+                        .and_then(|key| self.bound.attachment(key)).is_some()
+            }).count();
+            self.remaining.set(Some(visible));
+            visible
+        });
+        (visible, Some(visible))
+    }
+}
+
+impl<'a> ExactSizeIterator for Sprites<'a> {}
+
+/// Iterator over all slots sprites, `None` if the slot has nothing to show
+pub struct SlotSprites<'a> {
+    sprites: Sprites<'a>
+}
+
+impl<'a> Iterator for SlotSprites<'a> {
+    type Item = Option<Sprite<'a>>;
+    fn next(&mut self) -> Option<Option<Sprite<'a>>> {
+        self.sprites.next_slot()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sprites.iter.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for SlotSprites<'a> {}
+
+/// Sprite of a slot which has changed between two sample times
+#[derive(Debug)]
+pub struct SpriteDelta<'a> {
+    /// index of the slot, in draw order
+    pub slot_index: usize,
+    /// new sprite, `None` if the slot does not show anything anymore
+    pub sprite: Option<Sprite<'a>>
+}
+
+/// Iterator over the sprites which have changed between two sample times
+pub struct SpritesDelta<'a> {
+    previous: Sprites<'a>,
+    current: Sprites<'a>,
+    slot_index: usize,
+    epsilon: f32
+}
+
+impl<'a> Iterator for SpritesDelta<'a> {
+    type Item = SpriteDelta<'a>;
+    fn next(&mut self) -> Option<SpriteDelta<'a>> {
+        while let (Some(previous), Some(current)) = (self.previous.next_slot(), self.current.next_slot()) {
+            let slot_index = self.slot_index;
+            self.slot_index += 1;
+
+            let changed = match (previous, &current) {
+                (None, &None) => false,
+                (Some(ref p), &Some(ref c)) => p.attachment != c.attachment || p.color != c.color ||
+                                              !srt_approx_eq(&p.srt, &c.srt, self.epsilon),
+                _ => true
+            };
+
+            if changed {
+                return Some(SpriteDelta {
+                    slot_index: slot_index,
+                    sprite: current
+                });
+            }
+        }
+
+        // end of iter
+        None
+    }
+}
+
+/// converts a normalized color component to `[0, 255]`
+fn to_u8(c: f32) -> u8 {
+    (c.max(0.0).min(1.0) * 255.0).round() as u8
+}
+
+/// places a world srt on the bone the skeleton is mounted on
+fn mount(root: &skeleton::SRT, srt: &mut skeleton::SRT) {
+    srt.position = root.transform(srt.position);
+    srt.scale[0] *= root.scale[0];
+    srt.scale[1] *= root.scale[1];
+    let rotation = srt.rotation + root.rotation;
+    srt.set_rotation(rotation);
+}
+
+/// writes the 2D affine matrix of a srt into two packed rows, the last column is zero
+fn srt_to_rows(srt: &skeleton::SRT, rows: &mut [[f32; 4]]) {
+    let m = srt.to_matrix3x2();
+    rows[0] = [m[0][0], m[1][0], m[2][0], 0.0];
+    rows[1] = [m[0][1], m[1][1], m[2][1], 0.0];
+}
+
+/// expands a 2D affine matrix into a 3D one, as 4 columns
+fn to_matrix4(m: &[[f32; 2]; 3]) -> [[f32; 4]; 4] {
+    [
+        [m[0][0], m[0][1], 0.0, 0.0],
+        [m[1][0], m[1][1], 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [m[2][0], m[2][1], 0.0, 1.0],
+    ]
+}
+
+/// checks whether srts are equal, up to epsilon
+fn srt_approx_eq(a: &skeleton::SRT, b: &skeleton::SRT, epsilon: f32) -> bool {
+    (a.position[0] - b.position[0]).abs() <= epsilon &&
+    (a.position[1] - b.position[1]).abs() <= epsilon &&
+    (a.scale[0] - b.scale[0]).abs() <= epsilon &&
+    (a.scale[1] - b.scale[1]).abs() <= epsilon &&
+    (a.rotation - b.rotation).abs() <= epsilon
+}
+
+/// Iterator over triggered events
+pub struct Events<'a> {
+    iter: Chain<Iter<'a, json::EventKeyframe>, Iter<'a, json::EventKeyframe>>,
+    definitions: &'a IndexMap<String, json::Event>
+}
+
+impl<'a> Iterator for Events<'a> {
+    type Item = Event<'a>;
+    fn next(&mut self) -> Option<Event<'a>> {
+        self.iter.next().map(|e| {
+            let definition = self.definitions.get(&e.name);
+            Event {
+                name: &*e.name,
+                time: e.time,
+                int: e.int_.or(definition.and_then(|d| d.int_)).unwrap_or(0),
+                float: e.float_.or(definition.and_then(|d| d.float_)).unwrap_or(0.0),
+                string: e.string_.as_ref().or(definition.and_then(|d| d.string.as_ref())).map(|s| &**s)
+            }
+        })
+    }
+}
+
+/// Behavior of an `AnimationIter` or a `SpineController` once it reaches the end of the animation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnimationEnd {
+    /// ends the iterator
+    Stop,
+    /// holds the last frame forever
+    Clamp,
+    /// restarts the animation forever
+    Loop
+}
+
+/// Iterator over a constant period
+#[derive(Clone)]
+pub struct AnimationIter<'a> {
+    skin_animation: &'a SkinAnimation<'a>,
+    time: f32,
+    delta: f32,
+    end: AnimationEnd,
+    crossfade: f32
+}
+
+impl<'a> AnimationIter<'a> {
+
+    /// Restarts the iterator with a time scale applied to its period
+    ///
+    /// Speeds greater than 1 play the animation faster, lower than 1 slower.
+    /// Negative speeds play the animation backward, starting from its end.
+    pub fn speed(self, speed: f32) -> AnimationIter<'a> {
+        AnimationIter {
+            skin_animation: self.skin_animation,
+            time: if speed < 0.0 { self.skin_animation.get_duration() } else { 0f32 },
+            delta: self.delta * speed,
+            end: self.end,
+            crossfade: self.crossfade
+        }
+    }
+
+    /// Sets what happens once the end of the animation is reached, `AnimationEnd::Stop`
+    /// by default
+    pub fn end(self, end: AnimationEnd) -> AnimationIter<'a> {
+        AnimationIter {
+            end: end,
+            .. self
+        }
+    }
+
+    /// Crossfades the last `window` seconds of a looping animation into its first frame, see
+    /// `SkinAnimation::interpolate_looped`
+    pub fn crossfade(self, window: f32) -> AnimationIter<'a> {
+        AnimationIter {
+            crossfade: window,
+            .. self
+        }
+    }
+
+    /// Converts into an iterator yielding the time of each sprites along with them
+    pub fn timed(self) -> TimedAnimationIter<'a> {
+        TimedAnimationIter {
+            iter: self
+        }
+    }
+
+    /// Gets the time of the next sprites, `None` once the iterator is finished
+    pub fn get_time(&self) -> Option<f32> {
+        let duration = self.skin_animation.get_duration();
+        if self.time >= 0.0 && self.time <= duration {
+            return Some(self.time);
+        }
+        match self.end {
+            AnimationEnd::Stop => None,
+            AnimationEnd::Clamp => Some(self.time.max(0.0).min(duration)),
+            AnimationEnd::Loop if duration > 0.0 => Some((self.time % duration + duration) % duration),
+            AnimationEnd::Loop => Some(0.0)
+        }
+    }
+
+    /// Gets the time left from the next sprites to the end of the animation, in the playing
+    /// direction (i.e. to its start when played backward)
+    pub fn get_remaining(&self) -> f32 {
+        match self.get_time() {
+            Some(time) if self.delta < 0.0 => time,
+            Some(time) => self.skin_animation.get_duration() - time,
+            None => 0.0
+        }
+    }
+
+    /// interpolates the next sprites along with their time
+    fn next_timed(&mut self) -> Option<(f32, Sprites<'a>)> {
+        self.get_time().and_then(|time| {
+            self.time = time + self.delta;
+            let sprites = match self.end {
+                AnimationEnd::Loop => self.skin_animation.interpolate_looped(time, self.crossfade),
+                _ => self.skin_animation.interpolate(time)
+            };
+            sprites.map(|sprites| (time, sprites))
+        })
+    }
+}
+
+impl<'a> Iterator for AnimationIter<'a> {
+    type Item = Sprites<'a>;
+    fn next(&mut self) -> Option<Sprites<'a>> {
+        self.next_timed().map(|(_, sprites)| sprites)
+    }
+}
+
+/// Iterator over a constant period, yielding `(time, sprites)`
+#[derive(Clone)]
+pub struct TimedAnimationIter<'a> {
+    iter: AnimationIter<'a>
+}
+
+impl<'a> TimedAnimationIter<'a> {
+
+    /// Gets the underlying iterator, e.g. to read its time or remaining duration
+    pub fn get_iter(&self) -> &AnimationIter<'a> {
+        &self.iter
+    }
+}
+
+impl<'a> Iterator for TimedAnimationIter<'a> {
+    type Item = (f32, Sprites<'a>);
+    fn next(&mut self) -> Option<(f32, Sprites<'a>)> {
+        self.iter.next_timed()
+    }
+}
+
+/// Animated instance of a skeleton
+///
+/// The skeleton data is shared, so that many instances can be created from one document, each
+/// one with its own skin, animation, pose and time.
+pub struct SkeletonInstance {
+    skeleton: Arc<skeleton::Skeleton>,
+    binding: Binding,
+    pose: Pose,
+    /// attachments shown instead of skin ones, by slot index
+    overrides: HashMap<usize, Option<AttachmentKey>>,
+    /// attachments names of the overrides, by slot index
+    overrides_names: HashMap<usize, Option<String>>,
+    /// colors multiplied into the animated slots colors, by slot index
+    tints: HashMap<usize, [f32; 4]>,
+    time: f32,
+    /// keyframes positions at current time, moved along with the time
+    cursor: AnimationCursor
+}
+
+impl SkeletonInstance {
+
+    /// Creates a new instance showing the setup pose of a skin
+    pub fn new(skeleton: Arc<skeleton::Skeleton>, skin: &str) -> Result<SkeletonInstance, SkeletonError> {
+        let binding = try!(Binding::new(&skeleton, skin, None));
+        let pose = Pose::new(&skeleton);
+        Ok(SkeletonInstance {
+            skeleton: skeleton,
+            binding: binding,
+            pose: pose,
+            overrides: HashMap::new(),
+            overrides_names: HashMap::new(),
+            tints: HashMap::new(),
+            time: 0f32,
+            cursor: AnimationCursor::default()
+        })
+    }
+
+    /// binds the instance to its skeleton, with the keyframes cursor
+    fn bound(&self) -> Bound {
+        Bound { cursor: Some(&self.cursor), ..self.binding.bind(&self.skeleton) }
+    }
+
+    /// moves the keyframes cursor to current time, from the start if the animation changed
+    fn seek(&mut self, restart: bool) {
+        if restart {
+            self.cursor = AnimationCursor::default();
+        }
+        let (_, _, animation) = self.binding.resolve(&self.skeleton);
+        self.cursor.seek(animation, self.time);
+    }
+
+    /// Gets the shared skeleton data
+    pub fn get_skeleton(&self) -> &Arc<skeleton::Skeleton> {
+        &self.skeleton
+    }
+
+    /// Gets current skin name
+    pub fn get_skin(&self) -> &str {
+        &self.binding.skin
+    }
+
+    /// Changes the skin, keeping current animation, time and attachments overrides
+    ///
+    /// Fails if an overridden attachment cannot be found in the new skin.
+    pub fn set_skin(&mut self, skin: &str) -> Result<(), SkeletonError> {
+        let mut binding = try!(Binding::new(&self.skeleton, skin, self.binding.animation.as_ref().map(|a| &**a)));
+        binding.flip = self.binding.flip;
+        binding.opacity = self.binding.opacity;
+        binding.root = self.binding.root.clone();
+        let mut overrides = HashMap::new();
+        for (&slot, name) in &self.overrides_names {
+            let key = try!(find_override(&self.skeleton, &binding, slot, name.as_ref().map(|n| &**n)));
+            overrides.insert(slot, key);
+        }
+        self.binding = binding;
+        self.overrides = overrides;
+        Ok(())
+    }
+
+    /// Shows an attachment in a slot instead of the animated one, `None` to hide the slot
+    ///
+    /// The attachment is searched in current skin, then in default skin.
+    pub fn set_attachment(&mut self, slot: &str, attachment: Option<&str>) -> Result<(), SkeletonError> {
+        let slot_index = try!(skeleton::slot_index(slot, &self.skeleton.slots));
+        let key = try!(find_override(&self.skeleton, &self.binding, slot_index, attachment));
+        self.overrides.insert(slot_index, key);
+        self.overrides_names.insert(slot_index, attachment.map(|a| a.to_owned()));
+        Ok(())
+    }
+
+    /// Removes the attachment override of a slot
+    pub fn clear_attachment(&mut self, slot: &str) -> Result<(), SkeletonError> {
+        let slot_index = try!(skeleton::slot_index(slot, &self.skeleton.slots));
+        self.overrides.remove(&slot_index);
+        self.overrides_names.remove(&slot_index);
+        Ok(())
+    }
+
+    /// Multiplies a color, normalized in `[0, 1]`, into the animated color of a slot
+    ///
+    /// e.g. `[1.0, 0.0, 0.0, 1.0]` to flash a slot in red, or a team color.
+    pub fn set_slot_color(&mut self, slot: &str, color: [f32; 4]) -> Result<(), SkeletonError> {
+        let slot_index = try!(skeleton::slot_index(slot, &self.skeleton.slots));
+        self.tints.insert(slot_index, color);
+        Ok(())
+    }
+
+    /// Removes the color multiplied into the animated color of a slot
+    pub fn clear_slot_color(&mut self, slot: &str) -> Result<(), SkeletonError> {
+        let slot_index = try!(skeleton::slot_index(slot, &self.skeleton.slots));
+        self.tints.remove(&slot_index);
+        Ok(())
+    }
+
+    /// Gets current animation name
+    pub fn get_animation(&self) -> Option<&str> {
+        self.binding.animation.as_ref().map(|a| &**a)
+    }
+
+    /// Changes the animation (`None` for setup pose) and restarts time
+    pub fn set_animation(&mut self, animation: Option<&str>) -> Result<(), SkeletonError> {
+        let mut binding = try!(Binding::new(&self.skeleton, &self.binding.skin, animation));
+        binding.flip = self.binding.flip;
+        binding.opacity = self.binding.opacity;
+        binding.root = self.binding.root.clone();
+        self.binding = binding;
+        self.time = 0f32;
+        self.seek(true);
+        Ok(())
+    }
+
+    /// Mirrors the instance horizontally and/or vertically, see `SkinAnimation::set_flip`
+    pub fn set_flip(&mut self, flip_x: bool, flip_y: bool) {
+        self.binding.flip = [flip_x, flip_y];
+    }
+
+    /// Gets whether the instance is mirrored horizontally and vertically
+    pub fn get_flip(&self) -> (bool, bool) {
+        (self.binding.flip[0], self.binding.flip[1])
+    }
+
+    /// Sets the opacity multiplied into the alpha of all sprites, see `SkinAnimation::set_opacity`
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.binding.opacity = opacity;
+    }
+
+    /// Gets the opacity multiplied into the alpha of all sprites
+    pub fn get_opacity(&self) -> f32 {
+        self.binding.opacity
+    }
+
+    /// Places the root of the instance in the skeleton space of another skeleton, `None` to
+    /// draw it at the origin
+    pub fn set_root(&mut self, root: Option<skeleton::SRT>) {
+        self.binding.root = root;
+    }
+
+    /// Gets the transform of the root, see `set_root`
+    pub fn get_root(&self) -> Option<&skeleton::SRT> {
+        self.binding.root.as_ref()
+    }
+
+    /// Mounts the instance on a bone of another instance, e.g. a rider on the "saddle" bone
+    /// of a horse
+    ///
+    /// The root follows the bone at the current time of `parent`, this must be called each
+    /// frame after advancing the parent. The flip of the parent is copied so that both
+    /// skeletons are mirrored together, they are expected to share the same coordinate system.
+    pub fn mount(&mut self, parent: &SkeletonInstance, bone: &str) -> Result<(), SkeletonError> {
+        let index = try!(skeleton::bone_index(bone, &parent.skeleton.bones));
+        let srt = parent.bound().bone_skeleton_srt(index, parent.time, Some(&parent.pose));
+        if let Some(srt) = srt {
+            self.binding.root = Some(srt);
+        }
+        self.binding.flip = parent.binding.flip;
+        Ok(())
+    }
+
+    /// Interpolates sprites at current time along with the ones of a mounted instance, drawn
+    /// right after `slot`
+    ///
+    /// `None` if time is out of the animation of this instance, the mounted instance is hidden
+    /// if its own time is out of its animation.
+    pub fn interpolate_mounted<'b>(&'b self, mounted: &'b SkeletonInstance, slot: &str)
+        -> Result<Option<MountedSprites<'b>>, SkeletonError>
+    {
+        let slot_index = try!(skeleton::slot_index(slot, &self.skeleton.slots));
+        Ok(self.interpolate().map(|sprites| MountedSprites {
+            sprites: sprites.peekable(),
+            mounted: mounted.interpolate(),
+            slot_index: slot_index
+        }))
+    }
+
+    /// Gets duration of current animation
+    pub fn get_duration(&self) -> f32 {
+        self.binding.duration
+    }
+
+    /// Gets current time
+    pub fn get_time(&self) -> f32 {
+        self.time
+    }
+
+    /// Sets current time
+    ///
+    /// Keyframes are searched again when seeking backwards or far ahead, see `advance`.
+    pub fn set_time(&mut self, time: f32) {
+        self.time = time;
+        self.seek(false);
+    }
+
+    /// Advances current time by `delta` seconds
+    ///
+    /// The instance remembers the keyframes of the current time, so that advancing by small
+    /// steps only checks the next keyframes of each timeline.
+    pub fn advance(&mut self, delta: f32) {
+        self.time += delta;
+        self.seek(false);
+    }
+
+    /// Saves the playback state, to restore it later with `restore_state`
+    ///
+    /// Procedural bones modifications and the mount root are not saved.
+    pub fn save_state(&self) -> InstanceState {
+        let slots = &self.skeleton.slots;
+        InstanceState {
+            skin: self.binding.skin.clone(),
+            animation: self.binding.animation.clone(),
+            time: self.time,
+            flip: self.binding.flip,
+            opacity: self.binding.opacity,
+            attachments: (0..slots.len()).filter_map(|i| self.overrides_names.get(&i)
+                .map(|name| (slots[i].name.clone(), name.clone()))).collect(),
+            slot_colors: (0..slots.len()).filter_map(|i| self.tints.get(&i)
+                .map(|&color| (slots[i].name.clone(), color))).collect()
+        }
+    }
+
+    /// Restores a playback state saved from an instance of the same skeleton
+    ///
+    /// The instance is left unchanged if the state refers to missing skin, animation, slots or
+    /// attachments.
+    pub fn restore_state(&mut self, state: &InstanceState) -> Result<(), SkeletonError> {
+        let mut binding = try!(Binding::new(&self.skeleton, &state.skin, state.animation.as_ref().map(|a| &**a)));
+        binding.flip = state.flip;
+        binding.opacity = state.opacity;
+        binding.root = self.binding.root.clone();
+
+        let (mut overrides, mut overrides_names) = (HashMap::new(), HashMap::new());
+        for &(ref slot, ref name) in &state.attachments {
+            let slot_index = try!(skeleton::slot_index(slot, &self.skeleton.slots));
+            let key = try!(find_override(&self.skeleton, &binding, slot_index, name.as_ref().map(|n| &**n)));
+            overrides.insert(slot_index, key);
+            overrides_names.insert(slot_index, name.clone());
+        }
+        let mut tints = HashMap::new();
+        for &(ref slot, color) in &state.slot_colors {
+            tints.insert(try!(skeleton::slot_index(slot, &self.skeleton.slots)), color);
+        }
+
+        self.binding = binding;
+        self.overrides = overrides;
+        self.overrides_names = overrides_names;
+        self.tints = tints;
+        self.time = state.time;
+        self.seek(true);
+        Ok(())
+    }
+
+    /// Gets procedural bones modifications
+    pub fn get_pose(&self) -> &Pose {
+        &self.pose
+    }
+
+    /// Gets procedural bones modifications, to edit them
+    pub fn get_pose_mut(&mut self) -> &mut Pose {
+        &mut self.pose
+    }
+
+    /// Interpolates sprites at current time, `None` if time is out of the animation
+    pub fn interpolate(&self) -> Option<Sprites> {
+        self.bound().interpolate(self.time, Some(&self.pose)).map(|mut sprites| {
+            sprites.overrides = Some(&self.overrides);
+            sprites.tints = Some(&self.tints);
+            sprites
+        })
+    }
+
+    /// Interpolates sprites at current time in the buffers of a context, see
+    /// `SkinAnimation::interpolate_in`
+    pub fn interpolate_in<'b>(&'b self, context: &'b mut EvalContext) -> Option<Sprites<'b>> {
+        self.bound().interpolate_in(self.time, Some(&self.pose), context).map(|mut sprites| {
+            sprites.overrides = Some(&self.overrides);
+            sprites.tints = Some(&self.tints);
+            sprites
+        })
+    }
+
+    /// Gets the world srt of a bone at current time, see `SkinAnimation::bone_world_srt`
+    pub fn bone_world_srt(&self, name: &str) -> Option<skeleton::SRT> {
+        self.bound().bone_world_srt(name, self.time, Some(&self.pose))
+    }
+
+    /// Gets the names and world srts of all bones at current time
+    pub fn bones_world_srts(&self) -> Option<Vec<(&str, skeleton::SRT)>> {
+        self.bound().bones_world_srts(self.time, Some(&self.pose))
+    }
+
+    /// Gets the world matrices of all bones at current time, see `SkinAnimation::bone_matrices`
+    pub fn bone_matrices(&self) -> Option<Vec<[[f32; 2]; 3]>> {
+        self.bound().get_bones_srts(self.time, Some(&self.pose))
+            .map(|srts| srts.iter().map(|srt| srt.to_matrix3x2()).collect())
+    }
+
+    /// Gets the world matrices of all bones at current time as 4x4 matrices
+    pub fn bone_matrices4(&self) -> Option<Vec<[[f32; 4]; 4]>> {
+        self.bone_matrices().map(|matrices| matrices.iter().map(|m| to_matrix4(m)).collect())
+    }
+
+    /// Gets the axis-aligned bounding box of all visible attachments, see `SkinAnimation::bounds`
+    pub fn bounds(&self) -> Option<[[f32; 2]; 2]> {
+        self.interpolate().and_then(|sprites| sprites.bounds())
+    }
+
+    /// Gets the world polygons of all visible bounding boxes at current time, see
+    /// `SkinAnimation::collision_polygons`
+    pub fn collision_polygons(&self, regions: bool) -> Option<Vec<Vec<[f32; 2]>>> {
+        self.interpolate().map(|sprites| sprites.polygons(regions))
+    }
+
+    /// Finds the topmost slot containing the world `point`, see `SkinAnimation::hit_test`
+    pub fn hit_test(&self, point: [f32; 2]) -> Option<(&str, &str)> {
+        self.interpolate().and_then(|sprites| sprites.hit_test(point))
+    }
+
+    /// Gets the events triggered in `(from, to]`, see `SkinAnimation::events_between`
+    pub fn events_between(&self, from: f32, to: f32) -> Events {
+        self.bound().events_between(from, to)
+    }
+}
+
+/// Playback state of a `SkeletonInstance`, see `SkeletonInstance::save_state`
+///
+/// Serializable with `serde`, e.g. as json for save games.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstanceState {
+    /// skin name
+    pub skin: String,
+    /// animation name, `None` for the setup pose
+    pub animation: Option<String>,
+    /// current time
+    pub time: f32,
+    /// whether the instance is mirrored horizontally and vertically
+    pub flip: [bool; 2],
+    /// opacity multiplied into the alpha of all sprites
+    pub opacity: f32,
+    /// overridden attachments, by slot name, `None` for hidden slots
+    pub attachments: Vec<(String, Option<String>)>,
+    /// colors multiplied into the slots colors, by slot name
+    pub slot_colors: Vec<(String, [f32; 4])>
+}
+
+/// Iterator over the sprites of an instance and of an instance mounted on it, in draw order
+///
+/// `draw_index` of each sprite is relative to the skeleton it comes from.
+pub struct MountedSprites<'a> {
+    sprites: Peekable<Sprites<'a>>,
+    mounted: Option<Sprites<'a>>,
+    /// the mounted sprites are drawn after this slot
+    slot_index: usize
+}
+
+impl<'a> Iterator for MountedSprites<'a> {
+    type Item = Sprite<'a>;
+    fn next(&mut self) -> Option<Sprite<'a>> {
+        let slot_index = self.slot_index;
+        let before = self.sprites.peek().map(|s| s.draw_index <= slot_index).unwrap_or(false);
+        if !before {
+            if let Some(sprite) = self.mounted.as_mut().and_then(|m| m.next()) {
+                return Some(sprite);
+            }
+        }
+        self.sprites.next()
     }
 }
@@ -0,0 +1,272 @@
+//! Module to play an animation over time
+
+use skeleton::animation::{AnimationEnd, SampledPose, SkinAnimation, Sprites};
+use skeleton::events::Event;
+use std::f32::NEG_INFINITY;
+
+/// Callbacks notified by a `SpineController` while its animation is played
+///
+/// All methods do nothing by default.
+pub trait AnimationListener {
+    /// called by the first update of the controller
+    fn on_start(&mut self) {}
+    /// called when the end of the animation is reached
+    fn on_complete(&mut self) {}
+    /// called each time a looping animation restarts
+    fn on_loop(&mut self) {}
+    /// called for each event keyframe passed by an update, when playing forward
+    fn on_event(&mut self, _event: Event) {}
+}
+
+/// Stateful player of a skin animation
+///
+/// The controller keeps the current time of the animation, which is advanced by `update` with
+/// the elapsed time of each frame.
+pub struct SpineController<'a> {
+    animation: SkinAnimation<'a>,
+    time: f32,
+    speed: f32,
+    paused: bool,
+    end: AnimationEnd,
+    crossfade: f32,
+    started: bool,
+    listener: Option<Box<dyn AnimationListener + 'a>>
+}
+
+impl<'a> SpineController<'a> {
+
+    /// Creates a controller playing an animation from its start, at normal speed
+    pub fn new(animation: SkinAnimation<'a>) -> SpineController<'a> {
+        SpineController {
+            animation: animation,
+            time: 0f32,
+            speed: 1f32,
+            paused: false,
+            end: AnimationEnd::Stop,
+            crossfade: 0f32,
+            started: false,
+            listener: None
+        }
+    }
+
+    /// Gets the controlled animation
+    pub fn get_animation(&self) -> &SkinAnimation<'a> {
+        &self.animation
+    }
+
+    /// Sets what happens once the end of the animation is reached, `AnimationEnd::Stop`
+    /// by default
+    pub fn set_end(&mut self, end: AnimationEnd) {
+        self.end = end;
+    }
+
+    /// Crossfades the last `window` seconds of a looping animation into its first frame, see
+    /// `SkinAnimation::interpolate_looped`
+    pub fn set_loop_crossfade(&mut self, window: f32) {
+        self.crossfade = window;
+    }
+
+    /// Registers callbacks notified by `update`, `None` to remove them
+    pub fn set_listener(&mut self, listener: Option<Box<dyn AnimationListener + 'a>>) {
+        self.listener = listener;
+    }
+
+    /// Advances current time by `delta` seconds, scaled by the speed, unless paused
+    ///
+    /// Returns whether the animation completed or looped during the update, e.g. to chain
+    /// another animation once it ends.
+    pub fn update(&mut self, delta: f32) -> Progress {
+        if self.paused {
+            return Progress { completed: false, loops: 0 };
+        }
+
+        let finished = self.is_finished();
+        let duration = self.animation.get_duration();
+        let time = self.time + delta * self.speed;
+        let (time, loops) = match self.end {
+            AnimationEnd::Stop => (time, 0),
+            AnimationEnd::Clamp => (time.max(0.0).min(duration), 0),
+            AnimationEnd::Loop if duration > 0.0 =>
+                ((time % duration + duration) % duration, (time / duration).floor().abs() as u32),
+            AnimationEnd::Loop => (0.0, 0)
+        };
+        let previous = self.time;
+        self.time = time;
+        let progress = Progress {
+            completed: !finished && self.is_finished(),
+            loops: loops
+        };
+
+        let started = self.started;
+        self.started = true;
+        let animation = &self.animation;
+        if let Some(ref mut listener) = self.listener {
+            if !started {
+                listener.on_start();
+            }
+
+            // events at the current time are passed by the first update only
+            let from = if !started && previous <= 0.0 { NEG_INFINITY } else { previous };
+            let forward = self.speed * delta > 0.0;
+            let notify = |listener: &mut Box<dyn AnimationListener + 'a>, from: f32, to: f32| if forward {
+                for event in animation.events_between(from, to) {
+                    listener.on_event(event);
+                }
+            };
+
+            if loops == 0 {
+                notify(listener, from, time);
+            } else {
+                notify(listener, from, duration);
+                for i in 0..loops {
+                    listener.on_loop();
+                    notify(listener, NEG_INFINITY, if i + 1 < loops { duration } else { time });
+                }
+            }
+
+            if progress.completed {
+                listener.on_complete();
+            }
+        }
+        progress
+    }
+
+    /// Pauses the animation, `update` does not advance time anymore
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes a paused animation
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Gets whether the animation is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Jumps to a given time of the animation
+    pub fn seek(&mut self, time: f32) {
+        self.time = time;
+    }
+
+    /// Gets current time
+    pub fn get_time(&self) -> f32 {
+        self.time
+    }
+
+    /// Sets the time scale applied by `update`
+    ///
+    /// Speeds greater than 1 play the animation faster, lower than 1 slower.
+    /// Negative speeds play the animation backward.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    /// Gets the time scale applied by `update`
+    pub fn get_speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Gets whether the end of the animation has been reached, in the playing direction
+    ///
+    /// Looping animations never finish.
+    pub fn is_finished(&self) -> bool {
+        let duration = self.animation.get_duration();
+        match self.end {
+            AnimationEnd::Stop => self.time < 0.0 || self.time > duration,
+            AnimationEnd::Clamp if self.speed < 0.0 => self.time <= 0.0,
+            AnimationEnd::Clamp => self.time >= duration,
+            AnimationEnd::Loop => false
+        }
+    }
+
+    /// Interpolates sprites at current time, `None` if time is out of the animation
+    pub fn interpolate<'b>(&'b self) -> Option<Sprites<'b>> {
+        match self.end {
+            AnimationEnd::Loop => self.animation.interpolate_looped(self.time, self.crossfade),
+            _ => self.animation.interpolate(self.time)
+        }
+    }
+}
+
+/// What happened to the animation of a controller during an `update`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    /// whether the end of the animation has been reached during the update
+    pub completed: bool,
+    /// number of times a looping animation has restarted during the update
+    pub loops: u32
+}
+
+/// Controller advanced on a fixed simulation timestep
+///
+/// Elapsed time is accumulated and consumed by ticks of `step` seconds. Poses can then be
+/// rendered between the previous and the current ticks, for engines whose render loop is
+/// decoupled from their update loop.
+pub struct FixedStepController<'a> {
+    controller: SpineController<'a>,
+    step: f32,
+    accumulator: f32,
+    previous: f32
+}
+
+impl<'a> FixedStepController<'a> {
+
+    /// Creates a controller ticking every `step` seconds
+    ///
+    /// Panics if `step` is not strictly positive, as no tick could ever consume the elapsed time.
+    pub fn new(controller: SpineController<'a>, step: f32) -> FixedStepController<'a> {
+        assert!(step > 0.0, "fixed step must be positive, got {}", step);
+        let time = controller.get_time();
+        FixedStepController {
+            controller: controller,
+            step: step,
+            accumulator: 0f32,
+            previous: time
+        }
+    }
+
+    /// Gets the underlying controller
+    pub fn get_controller(&self) -> &SpineController<'a> {
+        &self.controller
+    }
+
+    /// Gets the underlying controller, to edit it
+    pub fn get_controller_mut(&mut self) -> &mut SpineController<'a> {
+        &mut self.controller
+    }
+
+    /// Accumulates `delta` seconds and runs as many ticks as possible
+    ///
+    /// Returns the progress of all the ticks run.
+    pub fn advance(&mut self, delta: f32) -> Progress {
+        let mut progress = Progress { completed: false, loops: 0 };
+        self.accumulator += delta;
+        while self.accumulator >= self.step {
+            self.accumulator -= self.step;
+            self.previous = self.controller.get_time();
+            let tick = self.controller.update(self.step);
+            progress.completed |= tick.completed;
+            progress.loops += tick.loops;
+        }
+        progress
+    }
+
+    /// Gets the fraction of a step accumulated since the last tick, in `[0, 1)`
+    pub fn get_alpha(&self) -> f32 {
+        self.accumulator / self.step
+    }
+
+    /// Samples the pose between the previous and the current ticks
+    ///
+    /// `alpha` is the weight of the current tick, usually `get_alpha()`. Times out of the
+    /// animation are clamped. Render the pose with `SkinAnimation::pose_sprites`.
+    pub fn sample(&self, alpha: f32) -> SampledPose {
+        let animation = self.controller.get_animation();
+        let sample = |time: f32| animation.sample(time.max(0.0).min(animation.get_duration()))
+            .expect("clamped time is always in the animation");
+        sample(self.previous).blend(&sample(self.controller.get_time()), alpha)
+    }
+}
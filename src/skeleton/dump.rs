@@ -0,0 +1,62 @@
+//! Module to dump sampled animations, e.g. to bake spine motion into other tools
+
+use skeleton::animation::SkinAnimation;
+
+/// Animation sampled at a fixed rate, see `dump_animation`
+///
+/// Serializable with `serde`, e.g. as json.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnimationDump {
+    /// frames per second
+    pub fps: f32,
+    /// bones names, in skeleton order
+    pub bones: Vec<String>,
+    /// slots names, in skeleton order
+    pub slots: Vec<String>,
+    /// sampled frames
+    pub frames: Vec<FrameDump>
+}
+
+/// Frame of an `AnimationDump`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrameDump {
+    /// time of the frame in the animation
+    pub time: f32,
+    /// world transform of each bone: x, y, rotation in radians, x scale and y scale
+    pub bones: Vec<[f32; 5]>,
+    /// attachment name, `None` if the slot shows nothing, and color of each slot
+    pub slots: Vec<(Option<String>, [f32; 4])>
+}
+
+/// Samples an animation at `fps` frames per second, from 0 to its duration included
+///
+/// World transforms are flipped and converted to the coordinate system of the skeleton, as
+/// the sprites. Panics if `fps` is not strictly positive and finite.
+pub fn dump_animation(animation: &SkinAnimation, fps: f32) -> AnimationDump {
+    assert!(fps > 0.0 && fps.is_finite(), "dump rate must be positive, got {}", fps);
+
+    let duration = animation.get_duration();
+    let count = (duration * fps).ceil() as usize + 1;
+    let frames = (0..count).map(|i| {
+        let time = (i as f32 / fps).min(duration);
+        let pose = animation.sample(time).expect("clamped time is always in the animation");
+        let bones = animation.pose_world_srts(&pose).into_iter()
+            .map(|(_, srt)| [srt.position[0], srt.position[1], srt.rotation, srt.scale[0], srt.scale[1]])
+            .collect();
+        let slots = animation.pose_sprites(&pose).slots().zip(pose.get_colors().iter())
+            .map(|(sprite, &color)| (sprite.map(|s| s.attachment.to_owned()), color)).collect();
+        FrameDump {
+            time: time,
+            bones: bones,
+            slots: slots
+        }
+    }).collect();
+
+    let skeleton = animation.get_skeleton();
+    AnimationDump {
+        fps: fps,
+        bones: skeleton.get_bones_names().into_iter().map(|name| name.to_owned()).collect(),
+        slots: skeleton.get_slots_names().into_iter().map(|name| name.to_owned()).collect(),
+        frames: frames
+    }
+}
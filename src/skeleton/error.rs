@@ -1,19 +1,20 @@
 //! Module to handle all spine errors
 
-use serialize::hex::FromHexError;
-use serialize::json::ParserError;
-use from_json::FromJsonError;
+use serde_json;
+use skeleton::json_path;
 use std::fmt;
+use std::io;
 use std::error::Error;
 
 /// Error that can happen while calculating an animation.
 pub enum SkeletonError {
 
-    /// Parser error
-    ParserError(ParserError),
+    /// Json parser error, with the path of the malformed value in the document
+    /// (e.g. `animations.walk.bones."left arm".rotate[3].curve`)
+    JsonError(String, serde_json::Error),
 
-    /// Parser error
-    FromJsonError(FromJsonError),
+    /// Error while reading the document
+    IoError(io::Error),
 
     /// The requested bone was not found.
     BoneNotFound(String),
@@ -24,23 +25,32 @@ pub enum SkeletonError {
     /// The requested slot was not found.
     SkinNotFound(String),
 
-    /// The requested slot was not found.
-    InvalidColor(FromHexError),
+    /// The color is not an hexadecimal `rrggbbaa` color.
+    InvalidColor(String),
 
     /// The requested animation was not found.
     AnimationNotFound(String),
+
+    /// The requested attachment was not found.
+    AttachmentNotFound(String),
+
+    /// The bone is not a child of the bone it was chained to.
+    InvalidBoneChain(String),
 }
 
 impl fmt::Debug for SkeletonError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
+            SkeletonError::IoError(ref e) => write!(f, "Cannot read document: {}", e),
             SkeletonError::BoneNotFound(ref name) => write!(f, "Cannot find bone '{}'", name),
             SkeletonError::SlotNotFound(ref name) => write!(f, "Cannot find slot '{}'", name),
             SkeletonError::SkinNotFound(ref name) => write!(f, "Cannot find skin '{}'", name),
             SkeletonError::AnimationNotFound(ref name) => write!(f, "Cannot find animation '{}'", name),
-            SkeletonError::InvalidColor(ref e)  => write!(f, "Cannot convert color to hexadecimal: {:?}", e),
-            SkeletonError::FromJsonError(ref e) => write!(f, "Cannot deserialize from json: {:?}", e),
-            SkeletonError::ParserError(ref e)   => write!(f, "Cannot deserialize from json: {:?}", e),
+            SkeletonError::AttachmentNotFound(ref name) => write!(f, "Cannot find attachment '{}'", name),
+            SkeletonError::InvalidBoneChain(ref name) => write!(f, "Bone '{}' is not a child of the chain", name),
+            SkeletonError::InvalidColor(ref color) => write!(f, "Cannot parse color '{}'", color),
+            SkeletonError::JsonError(ref path, ref e) if path.is_empty() => write!(f, "Cannot deserialize from json: {}", e),
+            SkeletonError::JsonError(ref path, ref e) => write!(f, "Cannot deserialize from json at {}: {}", path, e),
         }
     }
 }
@@ -54,31 +64,34 @@ impl fmt::Display for SkeletonError {
 impl Error for SkeletonError {
     fn description(&self) -> &str {
         match *self {
+            SkeletonError::IoError(_) => "error while reading json skeleton",
             SkeletonError::BoneNotFound(_) => "bone cannot be found in skeleton bones",
             SkeletonError::SlotNotFound(_) => "slot cannot be found in skeleton slots",
             SkeletonError::SkinNotFound(_) => "skin cannot be found in skeleton skins",
             SkeletonError::InvalidColor(_) => "color cannot be parsed",
             SkeletonError::AnimationNotFound(_) => "animation cannot be found in skeleton animations",
-            SkeletonError::FromJsonError(_) => "error while parsing json skeleton",
-            SkeletonError::ParserError(_) => "error while parsing json skeleton",
+            SkeletonError::AttachmentNotFound(_) => "attachment cannot be found in skin",
+            SkeletonError::InvalidBoneChain(_) => "bone is not a child of the previous bone of the chain",
+            SkeletonError::JsonError(..) => "error while parsing json skeleton",
         }
     }
 }
 
-impl From<FromHexError> for SkeletonError {
-    fn from(error: FromHexError) -> SkeletonError {
-        SkeletonError::InvalidColor(error)
+impl From<io::Error> for SkeletonError {
+    fn from(error: io::Error) -> SkeletonError {
+        SkeletonError::IoError(error)
     }
 }
 
-impl From<ParserError> for SkeletonError {
-    fn from(error: ParserError) -> SkeletonError {
-        SkeletonError::ParserError(error)
+impl From<serde_json::Error> for SkeletonError {
+    fn from(error: serde_json::Error) -> SkeletonError {
+        SkeletonError::JsonError(String::new(), error)
     }
 }
 
-impl From<FromJsonError> for SkeletonError {
-    fn from(error: FromJsonError) -> SkeletonError {
-        SkeletonError::FromJsonError(error)
+impl From<::serde_path_to_error::Error<serde_json::Error>> for SkeletonError {
+    fn from(error: ::serde_path_to_error::Error<serde_json::Error>) -> SkeletonError {
+        let path = json_path(&[], error.path());
+        SkeletonError::JsonError(path, error.into_inner())
     }
 }
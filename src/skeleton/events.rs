@@ -0,0 +1,36 @@
+//! Module to handle animations events
+
+/// Event triggered by an animation keyframe
+#[derive(Debug, Clone)]
+pub struct Event<'a> {
+    /// event name
+    pub name: &'a str,
+    /// keyframe time, in seconds since the start of the animation
+    pub time: f32,
+    /// integer value
+    pub int: i32,
+    /// float value
+    pub float: f32,
+    /// string value
+    pub string: Option<&'a str>,
+}
+
+/// Event playing an audio file
+#[derive(Debug, Clone)]
+pub struct AudioEvent<'a> {
+    /// event name
+    pub name: &'a str,
+    /// audio file path
+    pub audio: &'a str,
+    /// keyframe time, in seconds since the start of the animation
+    pub time: f32,
+    /// volume, from 0 to 1
+    pub volume: f32,
+    /// balance, from -1 (left) to 1 (right)
+    pub balance: f32,
+    /// time the sound must be queued to be heard at `time`, may be negative if it must be
+    /// queued before the animation starts
+    pub schedule_time: f32,
+    /// `schedule_time` converted in samples
+    pub schedule_sample: i64,
+}
@@ -0,0 +1,97 @@
+//! Module to solve inverse kinematics on sampled poses
+//!
+//! Targets are given in skeleton space, i.e. in world space before flipping
+//! (`SkinAnimation::set_flip`) and before the y-down conversion (`Skeleton::set_coordinate_system`).
+
+use skeleton::{base_rotation, bone_index, skeleton_srts, world_target, Skeleton, TO_RADIAN};
+use skeleton::animation::SampledPose;
+use skeleton::error::SkeletonError;
+use std::f32::consts::PI;
+
+/// Rotates two bones of a pose so that the tip of `child` reaches `target`
+///
+/// `child` must be a direct child of `parent`, the tip of the child is its end according to its
+/// length. When the target is out of reach, the chain is stretched towards it.
+/// `bend_positive` selects the side the joint bends to: counter-clockwise when `true`.
+pub fn solve_two_bone(skeleton: &Skeleton, pose: &mut SampledPose, parent: &str, child: &str,
+                      target: [f32; 2], bend_positive: bool) -> Result<(), SkeletonError>
+{
+    let parent = try!(bone_index(parent, &skeleton.bones));
+    let child = try!(bone_index(child, &skeleton.bones));
+    if skeleton.bones[child].parent_index != Some(parent) {
+        return Err(SkeletonError::InvalidBoneChain(skeleton.bones[child].name.clone()));
+    }
+    let target = world_target(skeleton, target);
+
+    let child_bone = &skeleton.bones[child];
+    let srts = skeleton_srts(skeleton, pose.get_bones());
+    let parent_srt = &srts[parent];
+    let base_rotation = base_rotation(skeleton, &srts, parent);
+
+    // child offset in parent frame and tip offset in child frame
+    let child_local = pose.get_bones()[child].clone();
+    let offset = [child_local.position[0] * parent_srt.scale[0],
+                  child_local.position[1] * parent_srt.scale[1]];
+    let (l1, a1) = ((offset[0] * offset[0] + offset[1] * offset[1]).sqrt(), offset[1].atan2(offset[0]));
+    let child_scale = if child_bone.inherit_scale { parent_srt.scale[0] } else { 1.0 } * child_local.scale[0];
+    let tip = child_bone.length * child_scale;
+    let (l2, a2) = (tip.abs(), if tip < 0.0 { PI } else { 0.0 });
+
+    // law of cosines on the triangle parent, joint, target
+    let to_target = [target[0] - parent_srt.position[0], target[1] - parent_srt.position[1]];
+    let distance = (to_target[0] * to_target[0] + to_target[1] * to_target[1]).sqrt()
+        .max((l1 - l2).abs()).min(l1 + l2);
+    let bend = if l1 > 0.0 && distance > 0.0 {
+        ((l1 * l1 + distance * distance - l2 * l2) / (2.0 * l1 * distance)).max(-1.0).min(1.0).acos()
+    } else {
+        0.0
+    };
+    let direction = to_target[1].atan2(to_target[0]);
+    let parent_rotation = direction - if bend_positive { bend } else { -bend } - a1;
+
+    let joint = [parent_srt.position[0] + l1 * (parent_rotation + a1).cos(),
+                 parent_srt.position[1] + l1 * (parent_rotation + a1).sin()];
+    let child_rotation = (target[1] - joint[1]).atan2(target[0] - joint[0]) - a2;
+
+    let bones = pose.get_bones_mut();
+    bones[parent].set_rotation(parent_rotation - base_rotation);
+    bones[child].set_rotation(child_rotation - if child_bone.inherit_rotation { parent_rotation } else { 0.0 });
+    Ok(())
+}
+
+/// Rotates a bone of a pose so that its X axis points at `target`
+///
+/// `limits` are the optional `[min, max]` local rotation of the bone, in degrees. `weight`
+/// blends the animated rotation (0) with the aiming one (1).
+pub fn aim_at(skeleton: &Skeleton, pose: &mut SampledPose, bone: &str, target: [f32; 2],
+              limits: Option<[f32; 2]>, weight: f32) -> Result<(), SkeletonError>
+{
+    let index = try!(bone_index(bone, &skeleton.bones));
+    let target = world_target(skeleton, target);
+    let srts = skeleton_srts(skeleton, pose.get_bones());
+    let position = srts[index].position;
+    let base_rotation = base_rotation(skeleton, &srts, index);
+
+    let mut rotation = wrap_angle((target[1] - position[1]).atan2(target[0] - position[0]) - base_rotation);
+    if let Some(limits) = limits {
+        rotation = rotation.max(limits[0] * TO_RADIAN).min(limits[1] * TO_RADIAN);
+    }
+
+    // blend along the shortest arc
+    let srt = &mut pose.get_bones_mut()[index];
+    let rotation = srt.rotation + wrap_angle(rotation - srt.rotation) * weight;
+    srt.set_rotation(rotation);
+    Ok(())
+}
+
+/// wraps an angle in `[-PI, PI]`
+fn wrap_angle(angle: f32) -> f32 {
+    let angle = angle % (2.0 * PI);
+    if angle > PI {
+        angle - 2.0 * PI
+    } else if angle < -PI {
+        angle + 2.0 * PI
+    } else {
+        angle
+    }
+}
@@ -0,0 +1,76 @@
+//! Module to load only some animations and skins of a document, e.g. to reduce the memory
+//! used by large characters files in constrained contexts
+
+use indexmap::IndexMap;
+use json;
+use skeleton::{parse_document, read_all, Skeleton};
+use skeleton::error::SkeletonError;
+use std::io::Read;
+
+/// Options to load a skeleton, dropping the animations and skins which are not needed
+///
+/// e.g. `SkeletonLoader::new().animations(&["walk", "run"]).skins(&["default"]).from_reader(file)`.
+/// The default skin is always kept, as other skins fall back to its attachments.
+#[derive(Default)]
+pub struct SkeletonLoader {
+    animations: Option<Vec<String>>,
+    skins: Option<Vec<String>>
+}
+
+impl SkeletonLoader {
+
+    /// Creates a loader keeping all animations and skins
+    pub fn new() -> SkeletonLoader {
+        SkeletonLoader::default()
+    }
+
+    /// Keeps only the named animations
+    pub fn animations(mut self, names: &[&str]) -> SkeletonLoader {
+        self.animations = Some(names.iter().map(|&name| name.to_owned()).collect());
+        self
+    }
+
+    /// Keeps only the named skins, and the default skin
+    pub fn skins(mut self, names: &[&str]) -> SkeletonLoader {
+        self.skins = Some(names.iter().map(|&name| name.to_owned()).collect());
+        self
+    }
+
+    /// Consumes reader (with json data) and returns a skeleton wrapping
+    ///
+    /// Fails if a kept animation or skin is not in the document.
+    pub fn from_reader<R: Read>(&self, reader: R) -> Result<Skeleton, SkeletonError> {
+        let data = try!(read_all(reader));
+        self.from_slice(&data)
+    }
+
+    /// Parses json data already in memory and returns a skeleton wrapping, see `from_reader`
+    pub fn from_slice(&self, data: &[u8]) -> Result<Skeleton, SkeletonError> {
+        let mut document = try!(parse_document(data));
+        if let Some(ref names) = self.animations {
+            try!(retain(&mut document.animations, names, None, SkeletonError::AnimationNotFound));
+        }
+        if let Some(ref names) = self.skins {
+            try!(retain(&mut document.skins, names, Some("default"), SkeletonError::SkinNotFound));
+        }
+        Skeleton::from_json(document, 1.0)
+    }
+}
+
+/// removes the entries of a map which are neither in `names` nor `kept`
+fn retain<V, F>(map: &mut Option<IndexMap<json::Name, V>>, names: &[String], kept: Option<&str>, not_found: F)
+    -> Result<(), SkeletonError> where F: Fn(String) -> SkeletonError
+{
+    let map = match *map {
+        Some(ref mut map) => map,
+        None if names.is_empty() => return Ok(()),
+        None => return Err(not_found(names[0].clone()))
+    };
+    for name in names {
+        if !map.keys().any(|key| **key == **name) {
+            return Err(not_found(name.clone()));
+        }
+    }
+    map.retain(|key, _| Some(&**key) == kept || names.iter().any(|name| **key == **name));
+    Ok(())
+}
@@ -0,0 +1,105 @@
+//! Module to restrict animations to a subset of bones and slots
+
+use skeleton::Skeleton;
+use skeleton::error::SkeletonError;
+use std::collections::HashMap;
+
+/// Set of bones and slots an animation is applied to
+///
+/// Bones and slots out of the mask keep their setup pose.
+#[derive(Debug, Clone)]
+pub struct BoneMask {
+    bones_indices: HashMap<String, usize>,
+    slots_indices: HashMap<String, usize>,
+    /// parent index, for each bone
+    parents: Vec<Option<usize>>,
+    /// bone index, for each slot
+    slots_bones: Vec<usize>,
+    bones: Vec<bool>,
+    slots: Vec<bool>,
+}
+
+impl BoneMask {
+
+    /// Creates an empty mask for the bones and slots of a skeleton
+    pub fn new(skeleton: &Skeleton) -> BoneMask {
+        BoneMask {
+            bones_indices: skeleton.bones.iter().enumerate().map(|(i, b)| (b.name.clone(), i)).collect(),
+            slots_indices: skeleton.slots.iter().enumerate().map(|(i, s)| (s.name.clone(), i)).collect(),
+            parents: skeleton.bones.iter().map(|b| b.parent_index).collect(),
+            slots_bones: skeleton.slots.iter().map(|s| s.bone_index).collect(),
+            bones: vec![false; skeleton.bones.len()],
+            slots: vec![false; skeleton.slots.len()]
+        }
+    }
+
+    /// Creates a mask containing a bone, all its descendants and their slots
+    pub fn from_root(skeleton: &Skeleton, root: &str) -> Result<BoneMask, SkeletonError> {
+        let mut mask = BoneMask::new(skeleton);
+        try!(mask.add_bone_tree(root));
+        Ok(mask)
+    }
+
+    /// Creates a mask containing only some slots, without any bone
+    pub fn from_slots(skeleton: &Skeleton, slots: &[&str]) -> Result<BoneMask, SkeletonError> {
+        let mut mask = BoneMask::new(skeleton);
+        for slot in slots {
+            try!(mask.add_slot(slot));
+        }
+        Ok(mask)
+    }
+
+    fn bone_index(&self, name: &str) -> Result<usize, SkeletonError> {
+        self.bones_indices.get(name).cloned().ok_or_else(|| SkeletonError::BoneNotFound(name.to_owned()))
+    }
+
+    /// Adds a bone, without its descendants, and the slots attached to it
+    pub fn add_bone(&mut self, name: &str) -> Result<(), SkeletonError> {
+        let index = try!(self.bone_index(name));
+        self.bones[index] = true;
+        for (slot, &bone) in self.slots.iter_mut().zip(self.slots_bones.iter()) {
+            *slot |= bone == index;
+        }
+        Ok(())
+    }
+
+    /// Adds a bone, all its descendants and their slots
+    pub fn add_bone_tree(&mut self, root: &str) -> Result<(), SkeletonError> {
+        let root = try!(self.bone_index(root));
+
+        // bones are ordered hierarchically, parents come before their children
+        let mut tree = vec![false; self.bones.len()];
+        for i in root..tree.len() {
+            tree[i] = i == root || self.parents[i].map(|p| tree[p]).unwrap_or(false);
+        }
+
+        for (bone, &in_tree) in self.bones.iter_mut().zip(tree.iter()) {
+            *bone |= in_tree;
+        }
+        for (slot, &bone) in self.slots.iter_mut().zip(self.slots_bones.iter()) {
+            *slot |= tree[bone];
+        }
+        Ok(())
+    }
+
+    /// Adds a single slot
+    pub fn add_slot(&mut self, name: &str) -> Result<(), SkeletonError> {
+        match self.slots_indices.get(name) {
+            Some(&i) => {
+                self.slots[i] = true;
+                Ok(())
+            },
+            None => Err(SkeletonError::SlotNotFound(name.to_owned()))
+        }
+    }
+
+    /// Checks whether a bone, by index in the skeleton, is in the mask
+    pub fn contains_bone(&self, index: usize) -> bool {
+        self.bones.get(index).cloned().unwrap_or(false)
+    }
+
+    /// Checks whether a slot, by index in the skeleton, is in the mask
+    pub fn contains_slot(&self, index: usize) -> bool {
+        self.slots.get(index).cloned().unwrap_or(false)
+    }
+}
@@ -4,21 +4,57 @@
 pub mod error;
 mod timelines;
 pub mod animation;
+pub mod events;
+pub mod pose;
+pub mod mask;
+pub mod scene;
+pub mod controller;
+pub mod state;
+pub mod sync;
+pub mod ik;
+pub mod spring;
+pub mod dump;
+pub mod loader;
 
+use atlas::{self, AtlasSet, Texture};
+use indexmap::IndexMap;
 use json;
-use from_json;
+use serde_json;
+use serde_json::value::RawValue;
+use serde_path_to_error::{self, Segment};
 use std::collections::HashMap;
+use std::fs::File;
 use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::OnceLock;
 use std::f32::consts::PI;
-use serialize::hex::{FromHex, FromHexError};
 
 // Reexport skeleton modules
 use self::error::SkeletonError;
-use self::timelines::{BoneTimeline, SlotTimeline};
+pub use self::timelines::{BoneTimeline, SlotTimeline, CurveMode, KeyCurve, Keyframe, Keyframes, TimelineCursor};
 use self::animation::SkinAnimation;
+use self::events::AudioEvent;
 
 const TO_RADIAN: f32 = PI / 180f32;
 
+/// parses a `rrggbbaa` hexadecimal color
+fn parse_color(color: &str) -> Result<[f32; 4], SkeletonError> {
+    let invalid = || SkeletonError::InvalidColor(color.to_owned());
+    if color.len() != 8 || !color.is_char_boundary(2) || !color.is_char_boundary(4) || !color.is_char_boundary(6) {
+        return Err(invalid());
+    }
+    let mut rgba = [0f32; 4];
+    for (i, c) in rgba.iter_mut().enumerate() {
+        let byte = try!(u8::from_str_radix(&color[i * 2..i * 2 + 2], 16).map_err(|_| invalid()));
+        *c = byte as f32 / 255.0;
+    }
+    Ok(rgba)
+}
+
+/// Separator between an attachment name and its material, e.g. "sword#glow"
+const MATERIAL_SEPARATOR: char = '#';
+
 fn bone_index(name: &str, bones: &[Bone]) -> Result<usize, SkeletonError> {
     bones.iter().position(|b| b.name == *name).ok_or_else(|| SkeletonError::BoneNotFound(name.to_owned()))
 }
@@ -27,34 +63,245 @@ fn slot_index(name: &str, slots: &[Slot]) -> Result<usize, SkeletonError> {
     slots.iter().position(|b| b.name == *name).ok_or_else(|| SkeletonError::SlotNotFound(name.to_owned()))
 }
 
+/// Computes the skeleton space srts of all bones from the local srts of a pose
+fn skeleton_srts(skeleton: &Skeleton, locals: &[SRT]) -> Vec<SRT> {
+    let mut bones = BoneArrays::new();
+    let mut srts = Vec::with_capacity(locals.len());
+    bones.load(locals);
+    bones.inherit(&skeleton.hierarchy);
+    bones.store(&mut srts);
+    srts
+}
+
+/// Converts a target in skeleton space into world space
+fn world_target(skeleton: &Skeleton, target: [f32; 2]) -> [f32; 2] {
+    match skeleton.coordinates {
+        CoordinateSystem::YUp => target,
+        CoordinateSystem::YDown => [target[0], -target[1]]
+    }
+}
+
+/// Gets the world rotation a bone local rotation is relative to, from the srts of
+/// `skeleton_srts`
+fn base_rotation(skeleton: &Skeleton, srts: &[SRT], index: usize) -> f32 {
+    let bone = &skeleton.bones[index];
+    match bone.parent_index {
+        Some(parent) if bone.inherit_rotation => srts[parent].rotation,
+        _ => 0.0
+    }
+}
+
+/// reads all the data of a reader, parsing from memory is much faster than from a reader
+fn read_all<R: Read>(mut reader: R) -> Result<Vec<u8>, SkeletonError> {
+    let mut data = Vec::new();
+    try!(reader.read_to_end(&mut data));
+    Ok(data)
+}
+
+/// parses a json document, errors give the path of the malformed value
+fn parse_document(data: &[u8]) -> Result<json::Document, SkeletonError> {
+    let mut deserializer = serde_json::Deserializer::from_slice(data);
+    let document = try!(serde_path_to_error::deserialize(&mut deserializer));
+    try!(deserializer.end());
+    Ok(document)
+}
+
+/// formats the path of a json value as `animations.walk.bones."left arm".rotate[3]`, `keys` are
+/// the keys of the object the path starts from
+fn json_path(keys: &[&str], path: &serde_path_to_error::Path) -> String {
+    let mut formatted = String::new();
+    for key in keys {
+        push_path_key(&mut formatted, key);
+    }
+    for segment in path.iter() {
+        match *segment {
+            Segment::Seq { index } => formatted.push_str(&format!("[{}]", index)),
+            Segment::Map { key: ref name } | Segment::Enum { variant: ref name } => push_path_key(&mut formatted, name),
+            Segment::Unknown => formatted.push_str(".?")
+        }
+    }
+    formatted
+}
+
+/// appends an object key to a json path, quoted if it is not an identifier
+fn push_path_key(path: &mut String, name: &str) {
+    if !path.is_empty() {
+        path.push('.');
+    }
+    if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+        path.push_str(name);
+    } else {
+        path.push_str(&format!("{:?}", name));
+    }
+}
+
+/// Multiplies all lengths of a document by `scale`
+///
+/// Animations are scaled when they are parsed, see `scale_animation`.
+fn scale_document(doc: &mut json::Document, scale: f32) {
+    let scale_opt = |v: &mut Option<f32>| if let Some(ref mut v) = *v { *v *= scale; };
+
+    for bone in doc.bones.iter_mut().flat_map(|bones| bones.iter_mut()) {
+        scale_opt(&mut bone.x);
+        scale_opt(&mut bone.y);
+        scale_opt(&mut bone.length);
+    }
+
+    for attachment in doc.skins.iter_mut().flat_map(|skins| skins.values_mut())
+        .flat_map(|slots| slots.values_mut()).flat_map(|attachments| attachments.values_mut())
+    {
+        scale_opt(&mut attachment.x);
+        scale_opt(&mut attachment.y);
+        scale_opt(&mut attachment.width);
+        scale_opt(&mut attachment.height);
+        for v in attachment.vertices.iter_mut().flat_map(|vertices| vertices.iter_mut()) {
+            *v *= scale;
+        }
+    }
+}
+
+/// Multiplies all lengths of an animation by `scale`, see `scale_document`
+fn scale_animation(animation: &mut json::Animation, scale: f32) {
+    let scale_opt = |v: &mut Option<f32>| if let Some(ref mut v) = *v { *v *= scale; };
+
+    for translate in animation.bones.iter_mut().flat_map(|bones| bones.values_mut())
+        .flat_map(|timeline| timeline.translate.iter_mut()).flat_map(|translate| translate.iter_mut())
+    {
+        scale_opt(&mut translate.x);
+        scale_opt(&mut translate.y);
+    }
+}
+
 /// Skeleton data converted from json and loaded into memory
+///
+/// The skeleton is `Send + Sync`, so it can be shared with an
+/// `Arc<Skeleton>` across threads, see `SkinAnimation::from_arc` and `SkeletonInstance`.
 pub struct Skeleton {
     /// bones for the skeleton, hierarchically ordered
     bones: Vec<Bone>,
+    /// bones hierarchy as flat arrays, in the same order
+    hierarchy: BoneHierarchy,
     /// slots
     slots: Vec<Slot>,
-    /// skins : key: skin name, value: slots attachments
-    skins: HashMap<String, Skin>,
-    /// all the animations
-    animations: HashMap<String, Animation>
+    /// skins : key: skin name, value: slots attachments, in document order
+    skins: IndexMap<String, Skin>,
+    /// events definitions
+    events: IndexMap<String, json::Event>,
+    /// all the animations, parsed when first requested, in document order
+    animations: IndexMap<String, LazyAnimation>,
+    /// scale of the lengths of the animations, see `from_reader_scaled`
+    scale: f32,
+    /// curve mode of the animations, see `set_curve_mode`
+    curve_mode: CurveMode,
+    /// whether rotations follow the shortest arc, see `set_shortest_rotation`
+    shortest_rotation: bool,
+    /// coordinate system of the output
+    coordinates: CoordinateSystem
+}
+
+/// Animation kept as json until it is first requested
+struct LazyAnimation {
+    /// json source, `None` for animations created from other ones
+    source: Option<Box<RawValue>>,
+    parsed: OnceLock<Animation>
+}
+
+impl LazyAnimation {
+    fn parsed(animation: Animation) -> LazyAnimation {
+        let parsed = OnceLock::new();
+        let _ = parsed.set(animation);
+        LazyAnimation {
+            source: None,
+            parsed: parsed
+        }
+    }
+}
+
+/// fails to compile if the skeleton or the shared animations stop being `Send + Sync`
+#[allow(dead_code)]
+fn assert_send_sync() {
+    fn send_sync<T: Send + Sync>() {}
+    send_sync::<Skeleton>();
+    send_sync::<SkinAnimation<'static>>();
+    send_sync::<animation::SkeletonInstance>();
+}
+
+/// Parses a json document, e.g. with `"...".parse::<Skeleton>()`
+impl FromStr for Skeleton {
+    type Err = SkeletonError;
+    fn from_str(s: &str) -> Result<Skeleton, SkeletonError> {
+        Skeleton::from_slice(s.as_bytes())
+    }
+}
+
+/// Coordinate system of world transforms, sprites and vertices
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoordinateSystem {
+    /// y axis goes up, as in Spine editor
+    YUp,
+    /// y axis goes down, as in most screen coordinates
+    YDown,
 }
 
 impl Skeleton {
 
     /// Consumes reader (with json data) and returns a skeleton wrapping
-    pub fn from_reader<R: Read>(mut reader: R) -> Result<Skeleton, SkeletonError> {
+    pub fn from_reader<R: Read>(reader: R) -> Result<Skeleton, SkeletonError> {
+        let data = try!(read_all(reader));
+        Skeleton::from_slice(&data)
+    }
+
+    /// Consumes reader (with gzip or zstd compressed json data, e.g. a `.json.gz` file) and
+    /// returns a skeleton wrapping
+    ///
+    /// Uncompressed json data is read as with `from_reader`.
+    #[cfg(feature = "compression")]
+    pub fn from_compressed_reader<R: Read>(reader: R) -> Result<Skeleton, SkeletonError> {
+        let data = try!(::compression::read_decompressed(reader));
+        Skeleton::from_slice(&data)
+    }
+
+    /// Opens a json file and returns a skeleton wrapping
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Skeleton, SkeletonError> {
+        let file = try!(File::open(path));
+        Skeleton::from_reader(file)
+    }
+
+    /// Parses json data already in memory and returns a skeleton wrapping
+    ///
+    /// Names are borrowed from `data` while converting the document, so that only the ones
+    /// kept by the skeleton are allocated.
+    pub fn from_slice(data: &[u8]) -> Result<Skeleton, SkeletonError> {
 
         // read and convert as json
-        let document = try!(from_json::Json::from_reader(&mut reader));
-        let document: json::Document = try!(from_json::FromJson::from_json(&document));
+        let document = try!(parse_document(data));
 
         // convert to skeleton (consumes document)
-        Skeleton::from_json(document)
+        Skeleton::from_json(document, 1.0)
+    }
+
+    /// Converts a json document already parsed, e.g. patched by an asset pipeline, and returns
+    /// a skeleton wrapping
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Skeleton, SkeletonError> {
+        let document = try!(serde_path_to_error::deserialize(value));
+        Skeleton::from_json(document, 1.0)
+    }
+
+    /// Consumes reader (with json data) and returns a skeleton wrapping, with all lengths
+    /// multiplied by `scale`
+    ///
+    /// Bones translations, attachments positions and sizes and translate timelines are scaled,
+    /// e.g. to load the same document for different resolutions.
+    pub fn from_reader_scaled<R: Read>(reader: R, scale: f32) -> Result<Skeleton, SkeletonError> {
+        let data = try!(read_all(reader));
+        let mut document = try!(parse_document(&data));
+        scale_document(&mut document, scale);
+        Skeleton::from_json(document, scale)
     }
 
     /// Creates a from_json skeleton
-    /// Consumes json::Document
-    fn from_json(doc: json::Document) -> Result<Skeleton, SkeletonError> {
+    /// Consumes json::Document, animations are parsed lazily and scaled by `scale`
+    fn from_json(doc: json::Document, scale: f32) -> Result<Skeleton, SkeletonError> {
 
         let mut bones = Vec::new();
         if let Some(jbones) = doc.bones {
@@ -72,57 +319,317 @@ impl Skeleton {
             }
         }
 
-        let mut animations = HashMap::new();
+        let mut animations = IndexMap::new();
         for janimations in doc.animations.into_iter() {
-            for (name, animation) in janimations.into_iter() {
-                let animation = try!(Animation::from_json(animation, &bones, &slots));
-                animations.insert(name, animation);
+            for (name, source) in janimations.into_iter() {
+                animations.insert(name.into_owned(), LazyAnimation {
+                    source: Some(source),
+                    parsed: OnceLock::new()
+                });
             }
         }
 
-        let mut skins = HashMap::new();
+        let mut skins = IndexMap::new();
         for jskin in doc.skins.into_iter() {
             for (name, jslots) in jskin.into_iter() {
                 let mut skin = Vec::new();
                 for (name, attachments) in jslots.into_iter() {
                     let slot_index = try!(slot_index(&name, &slots));
                     let attachments = attachments.into_iter().map(|(name, attachment)| {
-                        (name, Attachment::from_json(attachment))
+                        (name.into_owned(), Attachment::from_json(attachment))
                      }).collect();
                     skin.push((slot_index, attachments));
                 }
-                skins.insert(name, Skin {
+                skins.insert(name.into_owned(), Skin {
                     slots: skin
                 });
             }
         }
 
         Ok(Skeleton {
+            hierarchy: BoneHierarchy::new(&bones),
             bones: bones,
             slots: slots,
             skins: skins,
-            animations: animations
+            events: doc.events.unwrap_or(IndexMap::new()),
+            animations: animations,
+            scale: scale,
+            curve_mode: CurveMode::Approximate,
+            shortest_rotation: true,
+            coordinates: CoordinateSystem::YUp
         })
     }
 
+    /// gets an animation, parsing it when it is first requested
+    ///
+    /// Errors in the json of the animation and unknown bones or slots are reported here.
+    fn animation(&self, name: &str) -> Result<&Animation, SkeletonError> {
+        let lazy = try!(self.animations.get(name).ok_or_else(|| SkeletonError::AnimationNotFound(name.to_owned())));
+        if let Some(animation) = lazy.parsed.get() {
+            return Ok(animation);
+        }
+        let source = lazy.source.as_ref().expect("animations without source are parsed");
+        let animation = try!(self.parse_animation(name, source));
+
+        // another thread may have parsed it meanwhile, both are the same
+        let _ = lazy.parsed.set(animation);
+        Ok(lazy.parsed.get().expect("animation has just been parsed"))
+    }
+
+    /// Parses all the animations now instead of when they are first requested
+    ///
+    /// Animations are independent, with the `rayon` feature they are parsed on the rayon
+    /// thread pool. Returns the error of an animation which cannot be parsed.
+    pub fn preload_animations(&self) -> Result<(), SkeletonError> {
+        let names: Vec<&str> = self.animations.keys().map(|k| &**k).collect();
+        self.preload(&names)
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn preload(&self, names: &[&str]) -> Result<(), SkeletonError> {
+        for name in names {
+            try!(self.animation(name));
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "rayon")]
+    fn preload(&self, names: &[&str]) -> Result<(), SkeletonError> {
+        use rayon::prelude::*;
+        names.par_iter().map(|name| self.animation(name).map(|_| ())).collect()
+    }
+
+    /// parses the json of an animation against the bones and slots of the skeleton
+    fn parse_animation(&self, name: &str, source: &RawValue) -> Result<Animation, SkeletonError> {
+        let mut deserializer = serde_json::Deserializer::from_str(source.get());
+        let mut janimation: json::Animation = match serde_path_to_error::deserialize(&mut deserializer) {
+            Ok(animation) => animation,
+            Err(e) => {
+                let path = json_path(&["animations", name], e.path());
+                return Err(SkeletonError::JsonError(path, e.into_inner()));
+            }
+        };
+        if self.scale != 1.0 {
+            scale_animation(&mut janimation, self.scale);
+        }
+        let mut animation = try!(Animation::from_json(janimation, &self.bones, &self.slots));
+        animation.set_curve_mode(self.curve_mode);
+        animation.set_shortest_rotation(self.shortest_rotation);
+        Ok(animation)
+    }
+
+    /// Adds the animations of a json document exported for the same rig, e.g. one file per
+    /// animation
+    ///
+    /// Only the `animations` section of the document is read, their bones and slots are resolved
+    /// by name against this skeleton and their lengths are scaled as the ones of the skeleton.
+    /// Existing animations with the same names are replaced. Nothing is added if an animation
+    /// cannot be resolved.
+    pub fn merge_animations_from_reader<R: Read>(&mut self, reader: R) -> Result<(), SkeletonError> {
+        let data = try!(read_all(reader));
+        let mut deserializer = serde_json::Deserializer::from_slice(&data);
+        let document: json::AnimationsDocument = try!(serde_path_to_error::deserialize(&mut deserializer));
+        try!(deserializer.end());
+
+        let mut animations = Vec::new();
+        for (name, source) in document.animations.into_iter().flat_map(|animations| animations.into_iter()) {
+            let animation = try!(self.parse_animation(&name, &source));
+            animations.push((name.into_owned(), animation));
+        }
+        for (name, animation) in animations {
+            self.animations.insert(name, LazyAnimation::parsed(animation));
+        }
+        Ok(())
+    }
+
+    /// Sets the coordinate system of all outputs, `CoordinateSystem::YUp` by default
+    ///
+    /// With `CoordinateSystem::YDown`, world positions, rotations and scales are mirrored
+    /// vertically, so that transforms, matrices and vertices can be used directly in y-down
+    /// screen coordinates.
+    pub fn set_coordinate_system(&mut self, coordinates: CoordinateSystem) {
+        self.coordinates = coordinates;
+    }
+
+    /// Gets the coordinate system of all outputs
+    pub fn get_coordinate_system(&self) -> CoordinateSystem {
+        self.coordinates
+    }
+
     /// get skin
     pub fn get_skin<'a>(&'a self, name: &str) -> Result<&'a Skin, SkeletonError> {
         self.skins.get(name).ok_or_else(|| SkeletonError::SkinNotFound(name.to_owned()))
     }
 
+    /// Creates a new skin from `(slot name, attachment name, attachment)` entries
+    ///
+    /// Use `add_skin` to register it on the skeleton.
+    pub fn new_skin<'a, I>(&self, attachments: I) -> Result<Skin, SkeletonError>
+        where I: IntoIterator<Item=(&'a str, &'a str, Attachment)>
+    {
+        let mut slots: Vec<(usize, Vec<(String, Attachment)>)> = Vec::new();
+        for (slot, name, attachment) in attachments {
+            let slot_index = try!(slot_index(slot, &self.slots));
+            match slots.iter().position(|&(i, _)| i == slot_index) {
+                Some(pos) => insert_attachment(&mut slots[pos].1, name.to_owned(), attachment),
+                None => slots.push((slot_index, vec![(name.to_owned(), attachment)]))
+            }
+        }
+        Ok(Skin {
+            slots: slots
+        })
+    }
+
+    /// Registers a skin, replacing any skin with the same name
+    ///
+    /// The skin can then be used as any other skin, e.g. in `get_animated_skin`.
+    pub fn add_skin(&mut self, name: &str, skin: Skin) {
+        self.skins.insert(name.to_owned(), skin);
+    }
+
+    /// Merges existing skins into a new skin called `name`, see `Skin::merge`
+    ///
+    /// The new skin can then be used as any other skin, e.g. in `get_animated_skin`.
+    pub fn composite_skin(&mut self, name: &str, skins: &[&str]) -> Result<(), SkeletonError> {
+        let skin = {
+            let mut parts = Vec::with_capacity(skins.len());
+            for skin in skins {
+                parts.push(try!(self.get_skin(skin)));
+            }
+            Skin::merge(&parts)
+        };
+        self.add_skin(name, skin);
+        Ok(())
+    }
+
+    /// Binds the regions of an atlas page of `page_size` pixels to the attachments drawing them
+    ///
+    /// Sprites of bound attachments carry the texture coordinates of their region, and their
+    /// quad is shrunk to the packed part of trimmed regions (see `Texture::trim`). Call it once
+    /// per atlas page. Attachments created afterwards (e.g. with `new_skin`) are not bound.
+    pub fn bind_atlas<I: IntoIterator<Item=Texture>>(&mut self, textures: I, page_size: (u16, u16)) {
+        let regions: HashMap<String, ([[f32; 2]; 4], [[f32; 2]; 2])> = textures.into_iter().map(|t| {
+            let region = (t.uvs(page_size), t.trim());
+            (t.name, region)
+        }).collect();
+        self.bind_regions(|image| regions.get(image).map(|&(uvs, trim)| (uvs, trim, None)));
+    }
+
+    /// Binds the regions of a set of atlases to the attachments drawing them, see `bind_atlas`
+    ///
+    /// Indexed regions are bound to their lowest index. Regions of pages without size cannot be
+    /// bound. Sprites also carry the index of the page of their region in `AtlasSet::pages`.
+    pub fn bind_atlas_set(&mut self, atlases: &AtlasSet) {
+        self.bind_regions(|image| atlases.find(image).and_then(|(page, t)| {
+            atlases.pages()[page].size.map(|size| (t.uvs(size), t.trim(), Some(page)))
+        }));
+    }
+
+    /// binds the uvs, trim and page of the regions named after the images of the attachments
+    fn bind_regions<F>(&mut self, region: F)
+        where F: Fn(&str) -> Option<([[f32; 2]; 4], [[f32; 2]; 2], Option<usize>)>
+    {
+        for skin in self.skins.values_mut() {
+            for &mut (_, ref mut attachs) in skin.slots.iter_mut() {
+                for &mut (ref name, ref mut attach) in attachs.iter_mut() {
+                    let image = attach.name.as_ref().unwrap_or(name).clone();
+                    if let Some((uvs, trim, page)) = region(&image) {
+                        if attach.uvs.is_none() {
+                            attach.trim(trim);
+                        }
+                        attach.uvs = Some(uvs);
+                        attach.page = page;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Copies an animation of `source` into this skeleton, remapping its bones and slots by name
+    ///
+    /// `mapping` gives the name in this skeleton of source bones and slots whose name differs,
+    /// timelines of bones and slots which cannot be found are dropped. An existing animation
+    /// with the same name is replaced.
+    pub fn retarget_animation(&mut self, source: &Skeleton, name: &str, mapping: &HashMap<String, String>)
+        -> Result<(), SkeletonError>
+    {
+        let animation = {
+            let animation = try!(source.animation(name));
+            animation.retarget(source, self, mapping)
+        };
+        self.animations.insert(name.to_owned(), LazyAnimation::parsed(animation));
+        Ok(())
+    }
+
+    /// Creates an animation called `clip` from the keys of an animation in `[start, end]`
+    ///
+    /// Keys are shifted so that the clip starts at 0, and are sampled on the boundaries so that
+    /// the clip matches the original animation. An existing animation with the same name is
+    /// replaced.
+    pub fn clip_animation(&mut self, name: &str, clip: &str, start: f32, end: f32)
+        -> Result<(), SkeletonError>
+    {
+        let animation = try!(self.animation(name)).clip(start, end);
+        self.animations.insert(clip.to_owned(), LazyAnimation::parsed(animation));
+        Ok(())
+    }
+
+    /// Sets how bezier curves of all animations are evaluated, `CurveMode::Approximate` by default
+    pub fn set_curve_mode(&mut self, mode: CurveMode) {
+        self.curve_mode = mode;
+        for animation in self.animations.values_mut().filter_map(|a| a.parsed.get_mut()) {
+            animation.set_curve_mode(mode);
+        }
+    }
+
+    /// Sets whether rotations of all animations are interpolated along the shortest arc, `true`
+    /// by default
+    pub fn set_shortest_rotation(&mut self, shortest: bool) {
+        self.shortest_rotation = shortest;
+        for animation in self.animations.values_mut().filter_map(|a| a.parsed.get_mut()) {
+            animation.set_shortest_rotation(shortest);
+        }
+    }
+
     /// Gets a SkinAnimation which can interpolate slots at a given time
+    ///
+    /// Animations are parsed when they are first requested, so that errors in their json or
+    /// unknown bones and slots in their timelines are reported here rather than when loading.
     pub fn get_animated_skin<'a>(&'a self, skin: &str, animation: Option<&str>)
         -> Result<SkinAnimation<'a>, SkeletonError>
     {
         SkinAnimation::new(self, skin, animation)
     }
 
-    /// Returns the list of all skins names in this document.
+    /// Gets information about an animation
+    pub fn get_animation_info<'a>(&'a self, name: &str) -> Result<AnimationInfo<'a>, SkeletonError> {
+        self.animation(name).map(|animation| AnimationInfo { skeleton: self, animation: animation })
+    }
+
+    /// Returns the list of all skins names in this document, in document order
+    ///
+    /// Skins added with `add_skin` come after the ones of the document.
     pub fn get_skins_names(&self) -> Vec<&str> {
         self.skins.keys().map(|k| &**k).collect()
     }
 
-    /// Returns the list of all animations names in this document.
+    /// Returns the list of all bones names, in skeleton order
+    ///
+    /// Index of a bone in this list is its index in the bone matrices palettes
+    /// (`SkinAnimation::bone_matrices`).
+    pub fn get_bones_names(&self) -> Vec<&str> {
+        self.bones.iter().map(|b| &*b.name).collect()
+    }
+
+    /// Returns the list of all slots names, in skeleton order
+    pub fn get_slots_names(&self) -> Vec<&str> {
+        self.slots.iter().map(|s| &*s.name).collect()
+    }
+
+    /// Returns the list of all animations names in this document, in document order
+    ///
+    /// Animations created from other ones or merged from other documents come after the ones of
+    /// the document, an animation which is replaced keeps its position.
     pub fn get_animations_names(&self) -> Vec<&str> {
         self.animations.keys().map(|k| &**k).collect()
     }
@@ -134,39 +641,133 @@ impl Skeleton {
         let mut names: Vec<_> = self.skins.values()
             .flat_map(|skin| skin.slots.iter()
                 .flat_map(|&(_, ref attach)| attach.iter()
-                    .map(|(k, v)| v.name.as_ref().map(|n| &**n).unwrap_or(&*k))))
+                    .map(|&(ref k, ref v)| v.name.as_ref().map(|n| &**n).unwrap_or(&*k))))
             .collect();
 
         names.sort();
         names.dedup();
         names
     }
+
+    /// Returns the names of the images drawn by the attachments of all skins, i.e. the regions
+    /// they need in an atlas
+    ///
+    /// Bounding boxes are not drawn and materials are not part of the names.
+    pub fn get_images_names(&self) -> Vec<&str> {
+        let mut names: Vec<_> = self.skins.values()
+            .flat_map(|skin| skin.slots.iter()
+                .flat_map(|&(_, ref attach)| attach.iter()
+                    .filter(|&&(_, ref v)| !v.is_bounding_box())
+                    .map(|&(ref k, ref v)| v.name.as_ref().map(|n| &**n).unwrap_or(&*k))))
+            .collect();
+
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Returns the names of the images a skin may draw, i.e. the regions it needs in an atlas
+    ///
+    /// Images are the ones of the setup attachments and of the attachments shown by the
+    /// attachment timelines of all animations. Attachments missing from the skin are searched in
+    /// the default skin, the ones found in neither are ignored. All animations are parsed.
+    pub fn get_skin_images_names(&self, skin: &str) -> Result<Vec<&str>, SkeletonError> {
+        let skin = try!(self.get_skin(skin));
+        let default_skin = try!(self.get_skin("default"));
+        let mut animations = Vec::with_capacity(self.animations.len());
+        for name in self.animations.keys() {
+            animations.push(try!(self.animation(name)));
+        }
+        let setup = self.slots.iter().enumerate()
+            .filter_map(|(i, slot)| slot.attachment.as_ref().map(|name| (i, &**name)));
+        let swaps = animations.into_iter()
+            .flat_map(|animation| animation.slots.iter())
+            .flat_map(|&(i, ref timeline)| timeline.get_attachment_names().into_iter().map(move |name| (i, name)));
+
+        let mut names: Vec<_> = setup.chain(swaps).filter_map(|(i, name)| {
+            skin.image(i, name).or_else(|| default_skin.image(i, name))
+        }).filter_map(|image| image).collect();
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+
+    /// Returns the indices, in `AtlasSet::pages`, of the pages a skin may draw, see
+    /// `get_skin_images_names`
+    ///
+    /// Images without region in the atlases are ignored.
+    pub fn pages_for_skin(&self, atlases: &AtlasSet, skin: &str) -> Result<Vec<usize>, SkeletonError> {
+        let images = try!(self.get_skin_images_names(skin));
+        let mut pages: Vec<_> = images.iter().filter_map(|image| atlases.find(image)).map(|(page, _)| page).collect();
+        pages.sort();
+        pages.dedup();
+        Ok(pages)
+    }
 }
 
 /// Skin
 /// defines a set of slot with custom attachments
-/// slots: Vec<(slot_index, HashMap<custom_attachment_name, Attachment>)>
+/// slots: Vec<(slot_index, Vec<(custom_attachment_name, Attachment)>)>
 /// TODO: simpler architecture
+#[derive(Clone)]
 pub struct Skin {
-    /// all slots modified by the skin, the default skin contains all skeleton bones
-    slots: Vec<(usize, HashMap<String, Attachment>)>
+    /// all slots modified by the skin with their named attachments, the default skin contains
+    /// all skeleton bones
+    slots: Vec<(usize, Vec<(String, Attachment)>)>
+}
+
+/// adds an attachment to the attachments of a skin slot, replacing the one with the same name
+fn insert_attachment(attachs: &mut Vec<(String, Attachment)>, name: String, attachment: Attachment) {
+    match attachs.iter().position(|&(ref n, _)| *n == name) {
+        Some(pos) => attachs[pos].1 = attachment,
+        None => attachs.push((name, attachment))
+    }
 }
 
 impl Skin {
-    /// find attachment in a skin
-    fn find(&self, slot_index: usize, attach_name: &str) -> Option<&Attachment> {
-        self.slots.iter().filter_map(|&(i, ref attachs)|
-            if i == slot_index {
-                attachs.get(attach_name)
-            } else {
-                None
+    /// find the indices of the skin slot containing an attachment and of the attachment in
+    /// the skin slot
+    fn find_index(&self, slot_index: usize, attach_name: &str) -> Option<(usize, usize)> {
+        self.slots.iter().enumerate().filter(|&(_, &(i, _))| i == slot_index)
+            .filter_map(|(index, &(_, ref attachs))| {
+                attachs.iter().position(|&(ref name, _)| name == attach_name).map(|attach| (index, attach))
             }).next()
     }
 
+    /// gets the image drawn by an attachment of a slot, `None` if the skin has no such
+    /// attachment and `Some(None)` if it is not drawn
+    fn image<'a>(&'a self, slot_index: usize, name: &str) -> Option<Option<&'a str>> {
+        self.find_index(slot_index, name)
+            .map(|(i, attach)| &self.slots[i].1[attach])
+            .map(|&(ref k, ref attach)| if attach.is_bounding_box() {
+                None
+            } else {
+                Some(attach.name.as_ref().map(|n| &**n).unwrap_or(k))
+            })
+    }
+
+    /// Merges several skins into a new one
+    ///
+    /// Attachments of latter skins replace the attachments with the same name in the same slot.
+    pub fn merge(skins: &[&Skin]) -> Skin {
+        let mut slots: Vec<(usize, Vec<(String, Attachment)>)> = Vec::new();
+        for &(slot_index, ref attachs) in skins.iter().flat_map(|skin| skin.slots.iter()) {
+            match slots.iter().position(|&(i, _)| i == slot_index) {
+                Some(pos) => for &(ref name, ref attach) in attachs {
+                    insert_attachment(&mut slots[pos].1, name.clone(), attach.clone());
+                },
+                None => slots.push((slot_index, attachs.clone()))
+            }
+        }
+        Skin {
+            slots: slots
+        }
+    }
+
     /// get all attachments and their positions to setup the skeleton's skin
     pub fn attachment_positions(&self) -> Vec<(&str, &[[f32; 2]; 4])> {
         self.slots.iter().flat_map(|&(_, ref attachs)|
-            attachs.iter().map(|(name, ref attach)| (&**name, &attach.positions))).collect()
+            attachs.iter().map(|&(ref name, ref attach)| (&**name, &attach.positions))).collect()
     }
 }
 
@@ -181,6 +782,21 @@ struct Animation {
 
 impl Animation {
 
+    fn set_curve_mode(&mut self, mode: CurveMode) {
+        for &mut (_, ref mut timeline) in &mut self.bones {
+            timeline.set_curve_mode(mode);
+        }
+        for &mut (_, ref mut timeline) in &mut self.slots {
+            timeline.set_curve_mode(mode);
+        }
+    }
+
+    fn set_shortest_rotation(&mut self, shortest: bool) {
+        for &mut (_, ref mut timeline) in &mut self.bones {
+            timeline.set_shortest_rotation(shortest);
+        }
+    }
+
     /// Creates a from_json Animation
     fn from_json(animation: json::Animation, bones: &[Bone], slots: &[Slot])
         -> Result<Animation, SkeletonError>
@@ -214,6 +830,45 @@ impl Animation {
         })
     }
 
+    /// keeps the keys in `[start, end]`, shifted to start at 0
+    fn clip(&self, start: f32, end: f32) -> Animation {
+        let end = end.min(self.duration).max(start);
+        let in_clip = |time: f32| time >= start && time <= end;
+        Animation {
+            bones: self.bones.iter().map(|&(i, ref t)| (i, t.clip(start, end))).collect(),
+            slots: self.slots.iter().map(|&(i, ref t)| (i, t.clip(start, end))).collect(),
+            events: self.events.iter().filter(|e| in_clip(e.time)).map(|e| {
+                let mut e = e.clone();
+                e.time -= start;
+                e
+            }).collect(),
+            draworder: self.draworder.iter().filter(|d| in_clip(d.time)).map(|d| {
+                let mut d = d.clone();
+                d.time -= start;
+                d
+            }).collect(),
+            duration: end - start
+        }
+    }
+
+    /// remaps the timelines of an animation of `source` onto the bones and slots of `target`
+    fn retarget(&self, source: &Skeleton, target: &Skeleton, mapping: &HashMap<String, String>)
+        -> Animation
+    {
+        let map = |name: &str| mapping.get(name).map(|n| n.to_owned()).unwrap_or(name.to_owned());
+        Animation {
+            bones: self.bones.iter().filter_map(|&(i, ref timeline)| {
+                bone_index(&map(&source.bones[i].name), &target.bones).ok().map(|i| (i, timeline.clone()))
+            }).collect(),
+            slots: self.slots.iter().filter_map(|&(i, ref timeline)| {
+                slot_index(&map(&source.slots[i].name), &target.slots).ok().map(|i| (i, timeline.clone()))
+            }).collect(),
+            events: self.events.clone(),
+            draworder: self.draworder.clone(),
+            duration: self.duration
+        }
+    }
+
     fn duration(animation: &json::Animation) -> f32 {
         animation.bones.iter().flat_map(|bones| bones.values().flat_map(|timelines|{
             timelines.translate.iter().flat_map(|translate| translate.iter().map(|e| e.time))
@@ -224,10 +879,60 @@ impl Animation {
             timelines.attachment.iter().flat_map(|attachment| attachment.iter().map(|e| e.time))
             .chain(timelines.color.iter().flat_map(|color| color.iter().map(|e| e.time)))
         })))
+        .chain(animation.events.iter().flat_map(|events| events.iter().map(|e| e.time)))
+        .chain(animation.draworder.iter().flat_map(|draworder| draworder.iter().map(|e| e.time)))
         .fold(0.0f32, f32::max)
     }
 }
 
+/// Read-only information about an animation
+pub struct AnimationInfo<'a> {
+    skeleton: &'a Skeleton,
+    animation: &'a Animation
+}
+
+impl<'a> AnimationInfo<'a> {
+
+    /// Gets duration of the animation, i.e. the time of its last key (including events and
+    /// draw order keys)
+    pub fn get_duration(&self) -> f32 {
+        self.animation.duration
+    }
+
+    /// Gets the timelines of a bone, `None` if the bone is not animated
+    pub fn bone_timeline(&self, bone: &str) -> Result<Option<&'a BoneTimeline>, SkeletonError> {
+        let index = try!(bone_index(bone, &self.skeleton.bones));
+        Ok(self.animation.bones.iter().find(|&&(i, _)| i == index).map(|&(_, ref t)| t))
+    }
+
+    /// Gets the timelines of a slot, `None` if the slot is not animated
+    pub fn slot_timeline(&self, slot: &str) -> Result<Option<&'a SlotTimeline>, SkeletonError> {
+        let index = try!(slot_index(slot, &self.skeleton.slots));
+        Ok(self.animation.slots.iter().find(|&&(i, _)| i == index).map(|&(_, ref t)| t))
+    }
+
+    /// Gets all the events playing an audio file, sorted by time
+    ///
+    /// `latency` is the delay (in seconds) between queuing a sound and hearing it, it is used
+    /// to compute when each sound should be scheduled so that it is heard on its keyframe.
+    pub fn audio_events(&self, latency: f32, sample_rate: u32) -> Vec<AudioEvent<'a>> {
+        self.animation.events.iter().filter_map(|e| {
+            self.skeleton.events.get(&e.name).and_then(|event| event.audio.as_ref().map(|audio| {
+                let schedule_time = e.time - latency;
+                AudioEvent {
+                    name: &*e.name,
+                    audio: &**audio,
+                    time: e.time,
+                    volume: e.volume.or(event.volume).unwrap_or(1.0),
+                    balance: e.balance.or(event.balance).unwrap_or(0.0),
+                    schedule_time: schedule_time,
+                    schedule_sample: (schedule_time as f64 * sample_rate as f64).round() as i64
+                }
+            }))
+        }).collect()
+    }
+}
+
 /// Scale, Rotate, Translate struct
 #[derive(Debug, Clone)]
 pub struct SRT {
@@ -257,12 +962,30 @@ impl SRT {
         }
     }
 
+    /// sets rotation, in radians, along with its cosinus and sinus
+    fn set_rotation(&mut self, rotation: f32) {
+        self.rotation = rotation;
+        self.cos = rotation.cos();
+        self.sin = rotation.sin();
+    }
+
     /// apply srt on a 2D point (consumes the point)
     pub fn transform(&self, v: [f32; 2]) -> [f32; 2] {
         [self.cos * v[0] * self.scale[0] - self.sin * v[1] * self.scale[1] + self.position[0],
          self.sin * v[0] * self.scale[0] + self.cos * v[1] * self.scale[1] + self.position[1]]
     }
 
+    /// convert srt to a 2D affine matrix, as 3 columns: x axis, y axis and translation
+    ///
+    /// Unlike `to_matrix3`, scales are applied to both axes as in `transform`.
+    pub fn to_matrix3x2(&self) -> [[f32; 2]; 3] {
+        [
+            [ self.cos * self.scale[0], self.sin * self.scale[0]],
+            [-self.sin * self.scale[1], self.cos * self.scale[1]],
+            [ self.position[0], self.position[1]],
+        ]
+    }
+
     /// convert srt to a 3x3 transformation matrix (2D)
     pub fn to_matrix3(&self) -> [[f32; 3]; 3] {
         [
@@ -288,12 +1011,181 @@ impl SRT {
 struct Bone {
     name: String,
     parent_index: Option<usize>,
-    // length: f32,
+    length: f32,
     srt: SRT,
     inherit_scale: bool,
     inherit_rotation: bool
 }
 
+/// Parent indices, inherit flags and setup rotations of the bones as parallel arrays
+///
+/// Bones are hierarchically ordered so that a single pass over the arrays evaluates the parents
+/// before their children.
+struct BoneHierarchy {
+    parents: Vec<Option<usize>>,
+    inherit_rotation: Vec<bool>,
+    inherit_scale: Vec<bool>,
+    setup_rotations: Vec<f32>,
+    /// bones indices grouped by depth, bones of the same depth can be evaluated together
+    #[cfg(feature = "wide")]
+    levels: Vec<Vec<usize>>
+}
+
+impl BoneHierarchy {
+    fn new(bones: &[Bone]) -> BoneHierarchy {
+        BoneHierarchy {
+            parents: bones.iter().map(|b| b.parent_index).collect(),
+            inherit_rotation: bones.iter().map(|b| b.inherit_rotation).collect(),
+            inherit_scale: bones.iter().map(|b| b.inherit_scale).collect(),
+            setup_rotations: bones.iter().map(|b| b.srt.rotation).collect(),
+            #[cfg(feature = "wide")]
+            levels: BoneHierarchy::levels(bones)
+        }
+    }
+
+    #[cfg(feature = "wide")]
+    fn levels(bones: &[Bone]) -> Vec<Vec<usize>> {
+        let mut depths: Vec<usize> = Vec::with_capacity(bones.len());
+        let mut levels: Vec<Vec<usize>> = Vec::new();
+        for (i, bone) in bones.iter().enumerate() {
+            let depth = bone.parent_index.map_or(0, |p| depths[p] + 1);
+            if levels.len() <= depth {
+                levels.push(Vec::new());
+            }
+            levels[depth].push(i);
+            depths.push(depth);
+        }
+        levels
+    }
+}
+
+/// Bones srts as parallel arrays, evaluated in the order of a `BoneHierarchy`
+#[derive(Default)]
+struct BoneArrays {
+    positions: Vec<[f32; 2]>,
+    rotations: Vec<f32>,
+    scales: Vec<[f32; 2]>,
+    /// cosinus and sinus of the rotations
+    directions: Vec<[f32; 2]>
+}
+
+impl BoneArrays {
+    fn new() -> BoneArrays {
+        BoneArrays {
+            positions: Vec::new(),
+            rotations: Vec::new(),
+            scales: Vec::new(),
+            directions: Vec::new()
+        }
+    }
+
+    /// replaces the content of the arrays by srts
+    fn load(&mut self, srts: &[SRT]) {
+        self.positions.clear();
+        self.rotations.clear();
+        self.scales.clear();
+        self.directions.clear();
+        self.positions.extend(srts.iter().map(|srt| srt.position));
+        self.rotations.extend(srts.iter().map(|srt| srt.rotation));
+        self.scales.extend(srts.iter().map(|srt| srt.scale));
+        self.directions.extend(srts.iter().map(|srt| [srt.cos, srt.sin]));
+    }
+
+    /// converts local srts into skeleton space srts, parents come first in the hierarchy
+    #[cfg(not(feature = "wide"))]
+    fn inherit(&mut self, hierarchy: &BoneHierarchy) {
+        for i in 0..self.positions.len() {
+            if let Some(p) = hierarchy.parents[i] {
+                let ([cos, sin], scale, position) = (self.directions[p], self.scales[p], self.positions[p]);
+                let [x, y] = self.positions[i];
+                self.positions[i] = [cos * x * scale[0] - sin * y * scale[1] + position[0],
+                                     sin * x * scale[0] + cos * y * scale[1] + position[1]];
+                if hierarchy.inherit_rotation[i] {
+                    self.rotations[i] += self.rotations[p];
+                }
+                if hierarchy.inherit_scale[i] {
+                    self.scales[i][0] *= scale[0];
+                    self.scales[i][1] *= scale[1];
+                }
+            }
+
+            // re-calculate sin/cos only if rotation has changed
+            if self.rotations[i] != hierarchy.setup_rotations[i] {
+                self.directions[i] = [self.rotations[i].cos(), self.rotations[i].sin()];
+            }
+        }
+    }
+
+    /// converts local srts into skeleton space srts, by groups of 4 bones of the same depth
+    ///
+    /// Roots inherit from an identity parent, which leaves them unchanged.
+    #[cfg(feature = "wide")]
+    fn inherit(&mut self, hierarchy: &BoneHierarchy) {
+        use wide::CmpEq;
+
+        for level in &hierarchy.levels {
+            for bones in level.chunks(4) {
+                let parent = |i: usize| hierarchy.parents[i];
+                let (x, y) = (gather(bones, |i| self.positions[i][0]), gather(bones, |i| self.positions[i][1]));
+                let cos = gather(bones, |i| parent(i).map_or(1.0, |p| self.directions[p][0]));
+                let sin = gather(bones, |i| parent(i).map_or(0.0, |p| self.directions[p][1]));
+                let scale = [gather(bones, |i| parent(i).map_or(1.0, |p| self.scales[p][0])),
+                             gather(bones, |i| parent(i).map_or(1.0, |p| self.scales[p][1]))];
+                let position = [gather(bones, |i| parent(i).map_or(0.0, |p| self.positions[p][0])),
+                                gather(bones, |i| parent(i).map_or(0.0, |p| self.positions[p][1]))];
+                let position = [cos * x * scale[0] - sin * y * scale[1] + position[0],
+                                sin * x * scale[0] + cos * y * scale[1] + position[1]];
+
+                let inherited = |i: usize, flags: &[bool]| parent(i).filter(|_| flags[i]);
+                let rotation = gather(bones, |i| self.rotations[i])
+                    + gather(bones, |i| inherited(i, &hierarchy.inherit_rotation).map_or(0.0, |p| self.rotations[p]));
+                let scale = [gather(bones, |i| self.scales[i][0])
+                                 * gather(bones, |i| inherited(i, &hierarchy.inherit_scale).map_or(1.0, |p| self.scales[p][0])),
+                             gather(bones, |i| self.scales[i][1])
+                                 * gather(bones, |i| inherited(i, &hierarchy.inherit_scale).map_or(1.0, |p| self.scales[p][1]))];
+
+                // keeps the sin/cos of the bones whose rotation has not changed
+                let (sin, cos) = rotation.sin_cos();
+                let unchanged = rotation.cmp_eq(gather(bones, |i| hierarchy.setup_rotations[i]));
+                let cos = unchanged.blend(gather(bones, |i| self.directions[i][0]), cos);
+                let sin = unchanged.blend(gather(bones, |i| self.directions[i][1]), sin);
+
+                let lanes = [position[0].to_array(), position[1].to_array(), rotation.to_array(),
+                             scale[0].to_array(), scale[1].to_array(), cos.to_array(), sin.to_array()];
+                for (lane, &i) in bones.iter().enumerate() {
+                    self.positions[i] = [lanes[0][lane], lanes[1][lane]];
+                    self.rotations[i] = lanes[2][lane];
+                    self.scales[i] = [lanes[3][lane], lanes[4][lane]];
+                    self.directions[i] = [lanes[5][lane], lanes[6][lane]];
+                }
+            }
+        }
+    }
+
+    /// writes the arrays into srts, replacing the content of `srts`
+    fn store(&self, srts: &mut Vec<SRT>) {
+        srts.clear();
+        srts.extend(self.positions.iter().zip(self.rotations.iter()).zip(self.scales.iter()).zip(self.directions.iter())
+            .map(|(((&position, &rotation), &scale), &[cos, sin])| SRT {
+                scale: scale,
+                rotation: rotation,
+                position: position,
+                cos: cos,
+                sin: sin
+            }));
+    }
+}
+
+/// loads a value of up to 4 bones into the lanes of a SIMD vector, missing lanes are zero
+#[cfg(feature = "wide")]
+fn gather<F: Fn(usize) -> f32>(bones: &[usize], f: F) -> ::wide::f32x4 {
+    let mut lanes = [0f32; 4];
+    for (lane, &i) in lanes.iter_mut().zip(bones.iter()) {
+        *lane = f(i);
+    }
+    ::wide::f32x4::from(lanes)
+}
+
 impl Bone {
     fn from_json(bone: json::Bone, bones: &[Bone]) -> Result<Bone, SkeletonError> {
         let index = match bone.parent {
@@ -301,9 +1193,9 @@ impl Bone {
             None => None
         };
         Ok(Bone {
-            name: bone.name,
+            name: bone.name.into_owned(),
             parent_index: index,
-            // length: bone.length.unwrap_or(0f32),
+            length: bone.length.unwrap_or(0f32),
             srt: SRT::new(bone.scale_x.unwrap_or(1.0), bone.scale_y.unwrap_or(1.0),
                 bone.rotation.unwrap_or(0.0), bone.x.unwrap_or(0.0), bone.y.unwrap_or(0.0)),
             inherit_scale: bone.inherit_scale.unwrap_or(true),
@@ -316,7 +1208,7 @@ impl Bone {
 struct Slot {
     name: String,
     bone_index: usize,
-    color: [u8; 4],
+    color: [f32; 4],
     attachment: Option<String>
 }
 
@@ -324,51 +1216,102 @@ impl Slot {
     fn from_json(slot: json::Slot, bones: &[Bone]) -> Result<Slot, SkeletonError> {
         let bone_index = try!(bone_index(&slot.bone, &bones));
         let color = match slot.color {
-            Some(c) => {
-                let v = try!(c.from_hex());
-                if v.len() != 4 {
-                    return Err(SkeletonError::InvalidColor(FromHexError::InvalidHexLength));
-                }
-                [v[0], v[1], v[2], v[3]]
-            },
-            None => [255, 255, 255, 255]
+            Some(ref c) => try!(parse_color(c)),
+            None => [1.0, 1.0, 1.0, 1.0]
         };
 
         Ok(Slot {
-            name: slot.name,
+            name: slot.name.into_owned(),
             bone_index: bone_index,
             color: color,
-            attachment: slot.attachment
+            attachment: slot.attachment.map(json::Name::into_owned)
         })
     }
 }
 
-/// skeletom animation
-#[derive(Debug)]
-struct Attachment {
+/// skeleton attachment
+#[derive(Debug, Clone)]
+pub struct Attachment {
     name: Option<String>,
+    material: Option<String>,
     type_: json::AttachmentType,
-    positions: [[f32; 2]; 4]
+    positions: [[f32; 2]; 4],
+    vertices: Vec<[f32; 2]>,
+    uvs: Option<[[f32; 2]; 4]>,
+    page: Option<usize>
     // fps: Option<f32>,
     // mode: Option<String>,
-    //vertices: Option<Vec<??>>     // TODO: ?
 }
 
 impl Attachment {
-    /// converts json data into skeleton data
-    fn from_json(attachment: json::Attachment) -> Attachment {
-        let srt = SRT::new(attachment.scale_x.unwrap_or(1.0), attachment.scale_y.unwrap_or(1.0),
-                           attachment.rotation.unwrap_or(0.0),
-                           attachment.x.unwrap_or(0.0), attachment.y.unwrap_or(0.0));
-        let (w2, h2) = (attachment.width.unwrap_or(0f32) / 2.0,
-                        attachment.height.unwrap_or(0f32) / 2.0);
+
+    /// Creates a region attachment of `width` x `height`, placed by `srt` relative to its bone
+    ///
+    /// `name` is the name of the image to draw if it differs from the attachment name,
+    /// it can end with a material (e.g. "sword#glow").
+    pub fn new(name: Option<&str>, srt: &SRT, width: f32, height: f32) -> Attachment {
+        Attachment::region(name.map(|n| n.to_owned()), json::AttachmentType::Region, srt, width, height)
+    }
+
+    fn region(name: Option<String>, type_: json::AttachmentType, srt: &SRT, width: f32, height: f32)
+        -> Attachment
+    {
+        let (w2, h2) = (width / 2.0, height / 2.0);
+        let (name, material) = match name {
+            Some(ref name) if name.contains(MATERIAL_SEPARATOR) => {
+                let mut split = name.splitn(2, MATERIAL_SEPARATOR);
+                (split.next().map(|n| n.to_owned()), split.next().map(|m| m.to_owned()))
+            },
+            name => (name, None)
+        };
         Attachment {
-            name: attachment.name,
-            type_: attachment.type_.unwrap_or(json::AttachmentType::Region),
+            name: name,
+            material: material,
+            type_: type_,
             positions: [srt.transform([-w2,  h2]),
                         srt.transform([w2,  h2]),
                         srt.transform([w2,  -h2]),
-                        srt.transform([-w2,  -h2])]
+                        srt.transform([-w2,  -h2])],
+            vertices: Vec::new(),
+            uvs: None,
+            page: None
+        }
+    }
+
+    /// shrinks the quad to the `[left, bottom]`, `[right, top]` part of the region
+    fn trim(&mut self, trim: [[f32; 2]; 2]) {
+        self.positions = atlas::trim_quad(self.positions, trim);
+    }
+
+    /// gets the polygon used for hit-testing, relative to the bone
+    ///
+    /// Bounding boxes use their vertices, other attachments their quad.
+    fn polygon(&self) -> &[[f32; 2]] {
+        match self.type_ {
+            json::AttachmentType::BoundingBox => &self.vertices,
+            _ => &self.positions
+        }
+    }
+
+    /// checks whether the attachment is a bounding box
+    fn is_bounding_box(&self) -> bool {
+        match self.type_ {
+            json::AttachmentType::BoundingBox => true,
+            _ => false
         }
     }
+
+    /// converts json data into skeleton data
+    fn from_json(attachment: json::Attachment) -> Attachment {
+        let srt = SRT::new(attachment.scale_x.unwrap_or(1.0), attachment.scale_y.unwrap_or(1.0),
+                           attachment.rotation.unwrap_or(0.0),
+                           attachment.x.unwrap_or(0.0), attachment.y.unwrap_or(0.0));
+        let vertices = attachment.vertices.unwrap_or(Vec::new());
+        let mut attach = Attachment::region(attachment.name.map(json::Name::into_owned),
+                                            attachment.type_.unwrap_or(json::AttachmentType::Region),
+                                            &srt, attachment.width.unwrap_or(0f32),
+                                            attachment.height.unwrap_or(0f32));
+        attach.vertices = vertices.chunks(2).filter(|v| v.len() == 2).map(|v| [v[0], v[1]]).collect();
+        attach
+    }
 }
@@ -0,0 +1,146 @@
+//! Module to modify bones procedurally, on top of animations
+
+use skeleton::{Skeleton, SRT, TO_RADIAN};
+use skeleton::error::SkeletonError;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+/// How a pose value is applied to the animated bone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PoseMode {
+    /// added to (translation, rotation) or multiplied with (scale) the animated value
+    Relative,
+    /// replaces the animated value
+    Absolute,
+}
+
+/// Bone modifications
+#[derive(Debug, Clone, Default)]
+struct BonePose {
+    translation: Option<([f32; 2], PoseMode)>,
+    rotation: Option<(f32, PoseMode)>,
+    scale: Option<([f32; 2], PoseMode)>,
+    /// `[min, max]` local rotation, in radians
+    rotation_limits: Option<[f32; 2]>,
+    /// `[min, max]` local translation
+    translation_limits: Option<[[f32; 2]; 2]>,
+}
+
+/// Procedural bones modifications applied before world transforms are computed
+#[derive(Debug, Clone)]
+pub struct Pose {
+    bones_indices: HashMap<String, usize>,
+    bones: Vec<BonePose>,
+}
+
+impl Pose {
+
+    /// Creates an empty pose for the bones of a skeleton
+    pub fn new(skeleton: &Skeleton) -> Pose {
+        Pose {
+            bones_indices: skeleton.bones.iter().enumerate().map(|(i, b)| (b.name.clone(), i)).collect(),
+            bones: vec![BonePose::default(); skeleton.bones.len()]
+        }
+    }
+
+    fn bone_mut(&mut self, name: &str) -> Result<&mut BonePose, SkeletonError> {
+        match self.bones_indices.get(name) {
+            Some(&i) => Ok(&mut self.bones[i]),
+            None => Err(SkeletonError::BoneNotFound(name.to_owned()))
+        }
+    }
+
+    /// Sets bone local translation
+    pub fn set_bone_translation(&mut self, name: &str, x: f32, y: f32, mode: PoseMode)
+        -> Result<(), SkeletonError>
+    {
+        try!(self.bone_mut(name)).translation = Some(([x, y], mode));
+        Ok(())
+    }
+
+    /// Sets bone local rotation, in degrees
+    pub fn set_bone_rotation(&mut self, name: &str, rotation_deg: f32, mode: PoseMode)
+        -> Result<(), SkeletonError>
+    {
+        try!(self.bone_mut(name)).rotation = Some((rotation_deg * TO_RADIAN, mode));
+        Ok(())
+    }
+
+    /// Sets bone local scale
+    pub fn set_bone_scale(&mut self, name: &str, scale_x: f32, scale_y: f32, mode: PoseMode)
+        -> Result<(), SkeletonError>
+    {
+        try!(self.bone_mut(name)).scale = Some(([scale_x, scale_y], mode));
+        Ok(())
+    }
+
+    /// Clamps bone final local rotation in `[min, max]`, in degrees
+    ///
+    /// Limits are applied after the other modifications, so that procedural control combined
+    /// with animations does not bend joints unnaturally.
+    pub fn set_bone_rotation_limits(&mut self, name: &str, min_deg: f32, max_deg: f32)
+        -> Result<(), SkeletonError>
+    {
+        try!(self.bone_mut(name)).rotation_limits = Some([min_deg * TO_RADIAN, max_deg * TO_RADIAN]);
+        Ok(())
+    }
+
+    /// Clamps bone final local translation in `[min, max]`, see `set_bone_rotation_limits`
+    pub fn set_bone_translation_limits(&mut self, name: &str, min: [f32; 2], max: [f32; 2])
+        -> Result<(), SkeletonError>
+    {
+        try!(self.bone_mut(name)).translation_limits = Some([min, max]);
+        Ok(())
+    }
+
+    /// Removes all modifications of a bone
+    pub fn clear_bone(&mut self, name: &str) -> Result<(), SkeletonError> {
+        *try!(self.bone_mut(name)) = BonePose::default();
+        Ok(())
+    }
+
+    /// Removes all modifications
+    pub fn clear(&mut self) {
+        for bone in &mut self.bones {
+            *bone = BonePose::default();
+        }
+    }
+
+    /// Applies modifications on bones local srts, given in skeleton bones order
+    pub fn apply(&self, srts: &mut [SRT]) {
+        for (srt, bone) in srts.iter_mut().zip(self.bones.iter()) {
+            match bone.translation {
+                Some((t, PoseMode::Relative)) => {
+                    srt.position[0] += t[0];
+                    srt.position[1] += t[1];
+                },
+                Some((t, PoseMode::Absolute)) => srt.position = t,
+                None => ()
+            }
+            match bone.rotation {
+                Some((r, PoseMode::Relative)) => srt.rotation += r,
+                Some((r, PoseMode::Absolute)) => srt.rotation = r,
+                None => ()
+            }
+            match bone.scale {
+                Some((s, PoseMode::Relative)) => {
+                    srt.scale[0] *= s[0];
+                    srt.scale[1] *= s[1];
+                },
+                Some((s, PoseMode::Absolute)) => srt.scale = s,
+                None => ()
+            }
+            if let Some(limits) = bone.rotation_limits {
+                let mut rotation = srt.rotation % (2.0 * PI);
+                if rotation > PI { rotation -= 2.0 * PI; }
+                if rotation < -PI { rotation += 2.0 * PI; }
+                srt.rotation = rotation.max(limits[0]).min(limits[1]);
+            }
+            if let Some(limits) = bone.translation_limits {
+                for axis in 0..2 {
+                    srt.position[axis] = srt.position[axis].max(limits[0][axis]).min(limits[1][axis]);
+                }
+            }
+        }
+    }
+}
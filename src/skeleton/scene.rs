@@ -0,0 +1,89 @@
+//! Module to drive retained-mode renderers (scene graphs, DOM, SVG ...)
+//!
+//! Instead of drawing every sprite on each frame, a retained-mode renderer keeps one node per
+//! visible slot. `SceneAdapter` tracks these nodes and tells the `SceneGraph` when to create,
+//! update or remove them.
+
+use skeleton::animation::{Sprite, Sprites};
+
+/// Scene graph receiving nodes modifications
+pub trait SceneGraph {
+    /// Node created for a visible slot
+    type Node;
+
+    /// Creates a new node when a slot becomes visible or changes attachment
+    fn create_node(&mut self, slot_index: usize, sprite: &Sprite) -> Self::Node;
+
+    /// Updates the node of a slot which is still showing the same attachment
+    fn update_node(&mut self, node: &mut Self::Node, sprite: &Sprite);
+
+    /// Removes the node of a slot which is hidden or changes attachment
+    fn remove_node(&mut self, node: Self::Node);
+}
+
+/// Persistent nodes, one per visible slot
+pub struct SceneAdapter<N> {
+    /// slots nodes in draw order, with the attachment they were created for
+    nodes: Vec<Option<(String, N)>>
+}
+
+impl<N> SceneAdapter<N> {
+
+    /// Creates an adapter without any node
+    pub fn new() -> SceneAdapter<N> {
+        SceneAdapter {
+            nodes: Vec::new()
+        }
+    }
+
+    /// Synchronizes nodes with the sprites of a new frame
+    pub fn update<G: SceneGraph<Node=N>>(&mut self, graph: &mut G, sprites: Sprites) {
+        for (i, sprite) in sprites.slots().enumerate() {
+            if self.nodes.len() <= i {
+                self.nodes.push(None);
+            }
+
+            // remove node if slot is hidden or if attachment has changed
+            let changed = match (&self.nodes[i], &sprite) {
+                (&Some((ref name, _)), &Some(ref sprite)) => &**name != sprite.attachment,
+                (&Some(_), &None) => true,
+                _ => false
+            };
+            if changed {
+                if let Some((_, node)) = self.nodes[i].take() {
+                    graph.remove_node(node);
+                }
+            }
+
+            if let Some(sprite) = sprite {
+                match self.nodes[i] {
+                    Some((_, ref mut node)) => graph.update_node(node, &sprite),
+                    None => {
+                        let node = graph.create_node(i, &sprite);
+                        self.nodes[i] = Some((sprite.attachment.to_owned(), node));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Removes all nodes
+    pub fn clear<G: SceneGraph<Node=N>>(&mut self, graph: &mut G) {
+        for node in self.nodes.drain(..) {
+            if let Some((_, node)) = node {
+                graph.remove_node(node);
+            }
+        }
+    }
+
+    /// Gets the node of a slot, if visible
+    pub fn get_node(&self, slot_index: usize) -> Option<&N> {
+        self.nodes.get(slot_index).and_then(|n| n.as_ref().map(|&(_, ref node)| node))
+    }
+}
+
+impl<N> Default for SceneAdapter<N> {
+    fn default() -> SceneAdapter<N> {
+        SceneAdapter::new()
+    }
+}
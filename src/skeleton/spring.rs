@@ -0,0 +1,121 @@
+//! Module to simulate secondary motion (hair, tails, capes ...) on sampled poses
+//!
+//! The tip of each spring bone is a point mass pulled towards its animated position, so that it
+//! lags behind and oscillates when the parent bones move. Bones are simulated in skeleton space,
+//! see the `ik` module.
+
+use skeleton::{base_rotation, bone_index, skeleton_srts, Skeleton};
+use skeleton::animation::SampledPose;
+use skeleton::error::SkeletonError;
+
+/// Simulated bone
+struct SpringBone {
+    index: usize,
+    stiffness: f32,
+    damping: f32,
+    gravity: f32,
+    /// simulated tip position and velocity, `None` until the first update
+    state: Option<([f32; 2], [f32; 2])>
+}
+
+/// Spring simulation of some bones of a skeleton
+#[derive(Default)]
+pub struct SpringBones {
+    bones: Vec<SpringBone>
+}
+
+impl SpringBones {
+
+    /// Creates a simulation without any bone
+    pub fn new() -> SpringBones {
+        SpringBones::default()
+    }
+
+    /// Adds a simulated bone
+    ///
+    /// `stiffness` pulls the tip towards its animated position, `damping` slows it down and
+    /// `gravity` pulls it down, in skeleton units per second squared.
+    pub fn add_bone(&mut self, skeleton: &Skeleton, name: &str, stiffness: f32, damping: f32, gravity: f32)
+        -> Result<(), SkeletonError>
+    {
+        let index = try!(bone_index(name, &skeleton.bones));
+        self.bones.push(SpringBone {
+            index: index,
+            stiffness: stiffness,
+            damping: damping,
+            gravity: gravity,
+            state: None
+        });
+
+        // parents are simulated first
+        self.bones.sort_by_key(|b| b.index);
+        Ok(())
+    }
+
+    /// Restarts the simulation from the next animated pose
+    pub fn reset(&mut self) {
+        for bone in &mut self.bones {
+            bone.state = None;
+        }
+    }
+
+    /// Advances the simulation by `delta` seconds and rotates the simulated bones of a pose
+    ///
+    /// The pose is usually sampled from the animation at the current time, the simulation
+    /// keeps the state of the bones between updates.
+    pub fn update(&mut self, skeleton: &Skeleton, pose: &mut SampledPose, delta: f32) {
+        let mut srts = skeleton_srts(skeleton, pose.get_bones());
+        let mut rotated = Vec::new();
+        for bone in &mut self.bones {
+
+            // world srts are only computed again when a simulated parent has moved the bone
+            if rotated.iter().any(|&parent| is_ancestor(skeleton, parent, bone.index)) {
+                srts = skeleton_srts(skeleton, pose.get_bones());
+                rotated.clear();
+            }
+
+            let srt = &srts[bone.index];
+            let origin = srt.position;
+            let target = srt.transform([skeleton.bones[bone.index].length.max(1.0), 0.0]);
+            let length = ((target[0] - origin[0]).powi(2) + (target[1] - origin[1]).powi(2)).sqrt();
+
+            let (previous, mut velocity) = bone.state.unwrap_or((target, [0.0, 0.0]));
+            let mut position = previous;
+            for axis in 0..2 {
+                let gravity = if axis == 1 { -bone.gravity } else { 0.0 };
+                let force = bone.stiffness * (target[axis] - position[axis])
+                    - bone.damping * velocity[axis] + gravity;
+                velocity[axis] += force * delta;
+                position[axis] += velocity[axis] * delta;
+            }
+
+            // keeps the tip at the bone length from its origin, the velocity follows the constraint
+            let (dx, dy) = (position[0] - origin[0], position[1] - origin[1]);
+            let distance = (dx * dx + dy * dy).sqrt();
+            if distance > 0.0 {
+                position = [origin[0] + dx * length / distance, origin[1] + dy * length / distance];
+            }
+            if delta > 0.0 {
+                velocity = [(position[0] - previous[0]) / delta, (position[1] - previous[1]) / delta];
+            }
+            bone.state = Some((position, velocity));
+
+            let base_rotation = base_rotation(skeleton, &srts, bone.index);
+            let rotation = (position[1] - origin[1]).atan2(position[0] - origin[0]) - base_rotation;
+            pose.get_bones_mut()[bone.index].set_rotation(rotation);
+            rotated.push(bone.index);
+        }
+    }
+}
+
+/// checks whether a bone is a parent, grand-parent ... of another one
+fn is_ancestor(skeleton: &Skeleton, ancestor: usize, index: usize) -> bool {
+    let mut parent = skeleton.bones[index].parent_index;
+    while let Some(p) = parent {
+        if p == ancestor {
+            return true;
+        }
+        parent = skeleton.bones[p].parent_index;
+    }
+    false
+}
@@ -0,0 +1,294 @@
+//! Module to play animations on tracks and mix between them
+//!
+//! Track 0 is applied on the setup pose of the skin, each higher track is applied on top of
+//! the lower ones and only replaces the bones and slots it has timelines for.
+
+use skeleton::Skeleton;
+use skeleton::animation::{SampledPose, SkinAnimation};
+use skeleton::error::SkeletonError;
+
+/// Animation played on a track
+struct TrackEntry<'a> {
+    /// animation name and animation, `None` for the empty animation which shows the pose
+    /// of the lower tracks
+    animation: Option<(String, SkinAnimation<'a>)>,
+    time: f32,
+    looped: bool,
+    /// weight of the entry over the lower tracks
+    alpha: f32,
+    /// whether the entry being mixed out is held instead of faded out
+    hold_previous: bool,
+    /// entry being mixed out, with elapsed and total mix durations
+    mix: Option<(Box<TrackEntry<'a>>, f32, f32)>
+}
+
+impl<'a> TrackEntry<'a> {
+
+    fn update(&mut self, delta: f32) {
+        self.time += delta;
+        let mixed = match self.mix {
+            Some((ref mut from, ref mut elapsed, duration)) => {
+                from.update(delta);
+                *elapsed += delta;
+                *elapsed >= duration
+            },
+            None => false
+        };
+        if mixed {
+            self.mix = None;
+        }
+    }
+
+    /// time in the animation, wrapped if looped and clamped otherwise
+    fn animation_time(&self, animation: &SkinAnimation) -> f32 {
+        let duration = animation.get_duration();
+        if self.looped && duration > 0.0 {
+            self.time % duration
+        } else {
+            self.time.min(duration)
+        }
+    }
+
+    fn save(&self) -> TrackState {
+        let (mix_from, mix_elapsed, mix_duration) = match self.mix {
+            Some((ref from, elapsed, duration)) => (Some(Box::new(from.save())), elapsed, duration),
+            None => (None, 0f32, 0f32)
+        };
+        TrackState {
+            animation: self.animation.as_ref().map(|&(ref name, _)| name.clone()),
+            time: self.time,
+            looped: self.looped,
+            alpha: self.alpha,
+            hold_previous: self.hold_previous,
+            mix_from: mix_from,
+            mix_elapsed: mix_elapsed,
+            mix_duration: mix_duration
+        }
+    }
+
+    fn restore(skeleton: &'a Skeleton, skin: &str, state: &TrackState) -> Result<TrackEntry<'a>, SkeletonError> {
+        let animation = match state.animation {
+            Some(ref name) => Some((name.clone(), try!(skeleton.get_animated_skin(skin, Some(name))))),
+            None => None
+        };
+        let mix = match state.mix_from {
+            Some(ref from) => Some((Box::new(try!(TrackEntry::restore(skeleton, skin, from))),
+                                    state.mix_elapsed, state.mix_duration)),
+            None => None
+        };
+        Ok(TrackEntry {
+            animation: animation,
+            time: state.time,
+            looped: state.looped,
+            alpha: state.alpha,
+            hold_previous: state.hold_previous,
+            mix: mix
+        })
+    }
+
+    /// applies the entry on top of the pose of the lower tracks
+    fn apply(&self, base: &SampledPose) -> SampledPose {
+        let from = self.mix.as_ref().map(|&(ref from, elapsed, duration)| (from.apply(base), elapsed / duration));
+
+        // when holding the previous entry, bones and slots without timelines keep its pose
+        let over = match from {
+            Some((ref from, _)) if self.hold_previous => from,
+            _ => base
+        };
+        let pose = match self.animation {
+            Some((_, ref animation)) => animation.sample_over(self.animation_time(animation), over)
+                .expect("clamped time is always in the animation"),
+            None => over.clone()
+        };
+        let pose = match from {
+            Some((from, alpha)) => from.blend(&pose, alpha),
+            None => pose
+        };
+        if self.alpha < 1.0 {
+            base.blend(&pose, self.alpha)
+        } else {
+            pose
+        }
+    }
+}
+
+/// Playback state of a track, see `AnimationState::save_state`
+///
+/// Serializable with `serde`, e.g. as json for save games.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrackState {
+    /// animation name, `None` for the empty animation
+    pub animation: Option<String>,
+    /// time elapsed since the animation started
+    pub time: f32,
+    /// whether the animation loops
+    pub looped: bool,
+    /// weight of the track over the lower tracks
+    pub alpha: f32,
+    /// whether the animation being mixed out is held
+    pub hold_previous: bool,
+    /// state of the animation being mixed out
+    pub mix_from: Option<Box<TrackState>>,
+    /// elapsed mix duration
+    pub mix_elapsed: f32,
+    /// total mix duration
+    pub mix_duration: f32
+}
+
+/// Animations played on tracks, with mixing when an animation replaces another one
+pub struct AnimationState<'a> {
+    skeleton: &'a Skeleton,
+    skin: String,
+    setup: SkinAnimation<'a>,
+    tracks: Vec<Option<TrackEntry<'a>>>
+}
+
+impl<'a> AnimationState<'a> {
+
+    /// Creates a state without any animation, showing the setup pose of a skin
+    pub fn new(skeleton: &'a Skeleton, skin: &str) -> Result<AnimationState<'a>, SkeletonError> {
+        let setup = try!(skeleton.get_animated_skin(skin, None));
+        Ok(AnimationState {
+            skeleton: skeleton,
+            skin: skin.to_owned(),
+            setup: setup,
+            tracks: Vec::new()
+        })
+    }
+
+    /// Gets the setup pose of the skin, to draw sampled poses with `SkinAnimation::pose_sprites`
+    pub fn get_setup(&self) -> &SkinAnimation<'a> {
+        &self.setup
+    }
+
+    /// Plays an animation on a track from its start
+    ///
+    /// The animation previously played on the track is mixed out over `mix_duration` seconds.
+    pub fn set_animation(&mut self, track: usize, animation: &str, looped: bool, mix_duration: f32)
+        -> Result<(), SkeletonError>
+    {
+        let skin_animation = try!(self.skeleton.get_animated_skin(&self.skin, Some(animation)));
+        self.set_entry(track, Some((animation.to_owned(), skin_animation)), looped, mix_duration);
+        Ok(())
+    }
+
+    /// Mixes the animation of a track out over `mix_duration` seconds, back to the pose of the
+    /// lower tracks (the setup pose for track 0), then clears the track
+    pub fn set_empty_animation(&mut self, track: usize, mix_duration: f32) {
+        self.set_entry(track, None, false, mix_duration);
+    }
+
+    fn set_entry(&mut self, track: usize, animation: Option<(String, SkinAnimation<'a>)>, looped: bool,
+                 mix_duration: f32)
+    {
+        while self.tracks.len() <= track {
+            self.tracks.push(None);
+        }
+
+        let mix = match self.tracks[track].take() {
+            Some(from) if mix_duration > 0.0 => Some((Box::new(from), 0f32, mix_duration)),
+            _ => None
+        };
+        if animation.is_none() && mix.is_none() {
+            return;
+        }
+        self.tracks[track] = Some(TrackEntry {
+            animation: animation,
+            time: 0f32,
+            looped: looped,
+            alpha: 1f32,
+            hold_previous: false,
+            mix: mix
+        });
+    }
+
+    /// Stops the animation of a track immediately
+    pub fn clear_track(&mut self, track: usize) {
+        if let Some(entry) = self.tracks.get_mut(track) {
+            *entry = None;
+        }
+    }
+
+    /// Gets the name of the animation played on a track, `None` if the track is empty or
+    /// mixing out to the empty animation
+    pub fn get_animation(&self, track: usize) -> Option<&str> {
+        self.tracks.get(track).and_then(|entry| entry.as_ref())
+            .and_then(|entry| entry.animation.as_ref()).map(|&(ref name, _)| &**name)
+    }
+
+    /// Sets the weight, in `[0, 1]`, of the animation played on a track over the lower tracks
+    ///
+    /// e.g. 0.3 to partially apply an overlay animation. The alpha is reset to 1 when a new
+    /// animation is played on the track.
+    pub fn set_track_alpha(&mut self, track: usize, alpha: f32) {
+        if let Some(&mut Some(ref mut entry)) = self.tracks.get_mut(track) {
+            entry.alpha = alpha;
+        }
+    }
+
+    /// Holds the animation being mixed out of a track instead of fading it out
+    ///
+    /// The new animation is applied on top of the previous one, so that the bones it has no
+    /// timelines for keep the previous pose instead of dipping towards the lower tracks while
+    /// quickly chaining transitions. Like the alpha, it is reset when a new animation is played.
+    pub fn set_hold_previous(&mut self, track: usize, hold: bool) {
+        if let Some(&mut Some(ref mut entry)) = self.tracks.get_mut(track) {
+            entry.hold_previous = hold;
+        }
+    }
+
+    /// Gets the weight of the animation played on a track, `None` if the track is empty
+    pub fn get_track_alpha(&self, track: usize) -> Option<f32> {
+        self.tracks.get(track).and_then(|entry| entry.as_ref()).map(|entry| entry.alpha)
+    }
+
+    /// Gets the time elapsed since the animation of a track started, `None` if the track is empty
+    pub fn get_time(&self, track: usize) -> Option<f32> {
+        self.tracks.get(track).and_then(|entry| entry.as_ref()).map(|entry| entry.time)
+    }
+
+    /// Advances the time of all tracks by `delta` seconds
+    pub fn update(&mut self, delta: f32) {
+        for track in &mut self.tracks {
+            let done = match *track {
+                Some(ref mut entry) => {
+                    entry.update(delta);
+                    entry.animation.is_none() && entry.mix.is_none()
+                },
+                None => false
+            };
+            if done {
+                *track = None;
+            }
+        }
+    }
+
+    /// Saves the state of all tracks, `None` for empty tracks
+    pub fn save_state(&self) -> Vec<Option<TrackState>> {
+        self.tracks.iter().map(|entry| entry.as_ref().map(|entry| entry.save())).collect()
+    }
+
+    /// Restores the state of all tracks, saved from a state of the same skeleton and skin
+    ///
+    /// The state is left unchanged if an animation cannot be found.
+    pub fn restore_state(&mut self, tracks: &[Option<TrackState>]) -> Result<(), SkeletonError> {
+        let mut restored = Vec::with_capacity(tracks.len());
+        for track in tracks {
+            restored.push(match *track {
+                Some(ref state) => Some(try!(TrackEntry::restore(self.skeleton, &self.skin, state))),
+                None => None
+            });
+        }
+        self.tracks = restored;
+        Ok(())
+    }
+
+    /// Samples the pose of all tracks at their current time
+    pub fn sample(&self) -> SampledPose {
+        let mut pose = self.setup.sample(0.0).expect("setup pose is always in the animation");
+        for entry in self.tracks.iter().filter_map(|entry| entry.as_ref()) {
+            pose = entry.apply(&pose);
+        }
+        pose
+    }
+}
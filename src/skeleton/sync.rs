@@ -0,0 +1,92 @@
+//! Module to synchronize the phases of animations, e.g. to blend walk and run cycles with
+//! matching footfalls
+
+use skeleton::animation::SkinAnimation;
+use std::f32::NEG_INFINITY;
+
+/// Sync markers of a looping animation
+///
+/// Markers split the animation cycle into segments, the phase is a normalized position in the
+/// cycle: `i / n` at the i-th of `n` markers, interpolated linearly in time between markers.
+/// Two animations sampled at the same phase have their markers aligned, even if their
+/// durations or the spacing of their markers differ.
+#[derive(Debug, Clone)]
+pub struct SyncMarkers {
+    duration: f32,
+    markers: Vec<f32>
+}
+
+impl SyncMarkers {
+
+    /// Creates markers at given times of an animation of `duration` seconds
+    ///
+    /// Without any marker, the phase is the time normalized by the duration.
+    pub fn new(duration: f32, mut markers: Vec<f32>) -> SyncMarkers {
+        markers.sort_by(|a, b| a.partial_cmp(b).expect("marker times must not be NaN"));
+        SyncMarkers {
+            duration: duration,
+            markers: markers
+        }
+    }
+
+    /// Creates markers at the keyframes of an event, e.g. "footstep"
+    pub fn from_events(animation: &SkinAnimation, event: &str) -> SyncMarkers {
+        let duration = animation.get_duration();
+        let markers = animation.events_between(NEG_INFINITY, duration)
+            .filter(|e| e.name == event).map(|e| e.time).collect();
+        SyncMarkers::new(duration, markers)
+    }
+
+    /// Gets the marker times
+    pub fn get_markers(&self) -> &[f32] {
+        &self.markers
+    }
+
+    /// start and end times of a segment, the last one wraps to the first marker
+    fn segment(&self, i: usize) -> (f32, f32) {
+        let end = match self.markers.get(i + 1) {
+            Some(&end) => end,
+            None => self.markers[0] + self.duration
+        };
+        (self.markers[i], end)
+    }
+
+    /// Gets the phase, in `[0, 1)`, at a time of the animation
+    pub fn get_phase(&self, time: f32) -> f32 {
+        if self.duration <= 0.0 {
+            return 0.0;
+        }
+        let time = (time % self.duration + self.duration) % self.duration;
+        if self.markers.is_empty() {
+            return time / self.duration;
+        }
+
+        // times before the first marker belong to the last segment
+        let count = self.markers.len();
+        let (i, time) = match self.markers.iter().rposition(|&m| m <= time) {
+            Some(i) => (i, time),
+            None => (count - 1, time + self.duration)
+        };
+        let (start, end) = self.segment(i);
+        let progress = if end > start { (time - start) / (end - start) } else { 0.0 };
+        (i as f32 + progress) / count as f32
+    }
+
+    /// Gets the time of the animation at a phase, in `[0, 1)`
+    pub fn get_time(&self, phase: f32) -> f32 {
+        if self.duration <= 0.0 {
+            return 0.0;
+        }
+        let phase = phase - phase.floor();
+        if self.markers.is_empty() {
+            return phase * self.duration;
+        }
+
+        let count = self.markers.len();
+        let position = phase * count as f32;
+        let i = (position as usize).min(count - 1);
+        let (start, end) = self.segment(i);
+        let time = start + (position - i as f32) * (end - start);
+        time % self.duration
+    }
+}
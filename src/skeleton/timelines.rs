@@ -1,11 +1,56 @@
 use json;
 use skeleton;
-use serialize::hex::{FromHex, FromHexError};
 use skeleton::error::SkeletonError;
+use smallvec::SmallVec;
+use std::slice::Iter;
 
 const BEZIER_SEGMENTS: usize = 10;
 
-trait Interpolate {
+/// number of keyframes of a timeline stored inline, most timelines have only a few keyframes
+const INLINE_KEYFRAMES: usize = 4;
+
+/// number of keyframes scanned from the previous position of a cursor before searching
+const CURSOR_SCAN: usize = 4;
+
+/// Gets the number of keyframes at or before `elapsed`, keyframes being sorted by time
+fn key_position<K, F: Fn(&K) -> f32>(keys: &[K], time: F, elapsed: f32) -> usize {
+    let (mut low, mut high) = (0, keys.len());
+    while low < high {
+        let mid = (low + high) / 2;
+        if time(&keys[mid]) <= elapsed { low = mid + 1; } else { high = mid; }
+    }
+    low
+}
+
+/// Moves a position given by `key_position` to `elapsed`
+///
+/// When playing forward, the next keyframes are scanned from the previous position, otherwise
+/// the position is searched again.
+fn seek_position<K, F: Fn(&K) -> f32>(keys: &[K], time: F, elapsed: f32, position: &mut usize) {
+    let mut p = (*position).min(keys.len());
+    if p == 0 || time(&keys[p - 1]) <= elapsed {
+        for _ in 0..CURSOR_SCAN {
+            if p == keys.len() || time(&keys[p]) > elapsed {
+                *position = p;
+                return;
+            }
+            p += 1;
+        }
+    }
+    *position = key_position(keys, time, elapsed);
+}
+
+/// Positions of the keyframes found at a time in the timelines of a bone or a slot
+///
+/// Playback time usually advances monotonically, so moving a cursor with `BoneTimeline::seek`
+/// or `SlotTimeline::seek` only checks the next keyframes instead of searching all of them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimelineCursor {
+    /// number of keyframes at or before the cursor time, for each timeline
+    positions: [usize; 3]
+}
+
+pub trait Interpolate {
     fn interpolate(&self, next: &Self, percent: f32) -> Self;
 }
 
@@ -21,12 +66,27 @@ impl Interpolate for (f32, f32) {
     }
 }
 
-impl Interpolate for [u8; 4] {
+impl Interpolate for [f32; 4] {
+    fn interpolate(&self, next: &Self, percent: f32) -> Self {
+        [self[0].interpolate(&next[0], percent),
+         self[1].interpolate(&next[1], percent),
+         self[2].interpolate(&next[2], percent),
+         self[3].interpolate(&next[3], percent)]
+    }
+}
+
+impl Interpolate for skeleton::SRT {
     fn interpolate(&self, next: &Self, percent: f32) -> Self {
-        [(self[0] as f32).interpolate(&(next[0] as f32), percent) as u8,
-         (self[1] as f32).interpolate(&(next[1] as f32), percent) as u8,
-         (self[2] as f32).interpolate(&(next[2] as f32), percent) as u8,
-         (self[3] as f32).interpolate(&(next[3] as f32), percent) as u8]
+        let rotation = self.rotation.interpolate(&next.rotation, percent);
+        skeleton::SRT {
+            scale: [self.scale[0].interpolate(&next.scale[0], percent),
+                    self.scale[1].interpolate(&next.scale[1], percent)],
+            rotation: rotation,
+            position: [self.position[0].interpolate(&next.position[0], percent),
+                       self.position[1].interpolate(&next.position[1], percent)],
+            cos: rotation.cos(),
+            sin: rotation.sin()
+        }
     }
 }
 
@@ -70,16 +130,10 @@ impl_curve!(json::BoneRotateTimeline, f32, |t: &json::BoneRotateTimeline| {
     Ok(angle)
 });
 
-impl_curve!(json::SlotColorTimeline, [u8; 4], |t: &json::SlotColorTimeline| {
+impl_curve!(json::SlotColorTimeline, [f32; 4], |t: &json::SlotColorTimeline| {
     Ok(match t.color {
-        Some(ref c) => {
-            let v = try!(c.from_hex());
-            if v.len() != 4 {
-                return Err(SkeletonError::InvalidColor(FromHexError::InvalidHexLength));
-            }
-            [v[0], v[1], v[2], v[3]]
-        },
-        None => [255, 255, 255, 255]
+        Some(ref c) => try!(skeleton::parse_color(c)),
+        None => [1.0, 1.0, 1.0, 1.0]
     })
 });
 
@@ -95,10 +149,11 @@ impl Curve<Option<String>> for json::SlotAttachmentTimeline {
     }
 }
 
+#[derive(Clone)]
 struct CurveTimeline<T> {
     time: f32,
     curve: json::TimelineCurve,
-    points: Option<(Vec<f32>, Vec<f32>)>,    // bezier curve interpolations points
+    points: Option<usize>,    // offset of the bezier curve interpolations points in the samples
     value: T,
 }
 
@@ -109,7 +164,8 @@ impl<T> CurveTimeline<T> {
     /// from this keyframe to the next.
     /// cx1 and cx2 are from 0 to 1, representing the percent of time between the two keyframes.
     /// cy1 and cy2 are the percent of the difference between the keyframe's values.
-    fn compute_points(curve: &json::TimelineCurve) -> Option<(Vec<f32>, Vec<f32>)> {
+    /// The (x, y) points are appended to `samples`, their offset is returned.
+    fn compute_points(curve: &json::TimelineCurve, samples: &mut Vec<[f32; 2]>) -> Option<usize> {
 
         let (cx1, cy1, cx2, cy2) = match *curve {
             json::TimelineCurve::CurveStepped |
@@ -128,11 +184,10 @@ impl<T> CurveTimeline<T> {
         let (mut ddfx, mut ddfy) = (tmp1x * pre4 + tmp2x * pre5, tmp1y * pre4 + tmp2y * pre5);
         let (dddfx, dddfy) = (tmp2x * pre5, tmp2y * pre5);
 
-        let (mut vec_x, mut vec_y) = (Vec::with_capacity(BEZIER_SEGMENTS), Vec::with_capacity(BEZIER_SEGMENTS));
+        let offset = samples.len();
         let (mut x, mut y) = (dfx, dfy);
         for _ in 0..BEZIER_SEGMENTS {
-            vec_x.push(x);
-            vec_y.push(y);
+            samples.push([x, y]);
             dfx += ddfx;
             dfy += ddfy;
             ddfx += dddfx;
@@ -140,34 +195,145 @@ impl<T> CurveTimeline<T> {
             x += dfx;
             y += dfy;
         }
-        Some((vec_x, vec_y))
+        Some(offset)
     }
 
     /// Get percent conversion depending on curve type
-    fn get_percent(&self, percent: f32) -> f32 {
-
-
-        let &(ref x,  ref y) = match self.curve {
-            json::TimelineCurve::CurveStepped    => return 0f32,
-            json::TimelineCurve::CurveLinear     => return percent,
-            json::TimelineCurve::CurveBezier(..) => self.points.as_ref().unwrap()
+    fn get_percent(&self, percent: f32, mode: CurveMode, samples: &[[f32; 2]]) -> f32 {
+
+        let points = match (&self.curve, mode) {
+            (&json::TimelineCurve::CurveStepped, _)    => return 0f32,
+            (&json::TimelineCurve::CurveLinear, _)     => return percent,
+            (&json::TimelineCurve::CurveBezier(ref c), CurveMode::Exact) => {
+                let p = [[0.0, 0.0], [c[0], c[1]], [c[2], c[3]], [1.0, 1.0]];
+                return bezier_point(&p, bezier_param(&p, percent))[1];
+            },
+            (&json::TimelineCurve::CurveBezier(..), CurveMode::Approximate) => {
+                let offset = self.points.unwrap();
+                &samples[offset..offset + BEZIER_SEGMENTS]
+            }
         };
 
         // bezier curve
-        match x.iter().position(|&xi| percent < xi) {
-            Some(0) => y[0] * percent / x[0],
-            Some(i) => y[i] + (y[i] - y[i - 1]) * (percent - x[i - 1]) / (x[i] - x[i - 1]),
+        match points.iter().position(|p| percent < p[0]) {
+            Some(0) => points[0][1] * percent / points[0][0],
+            Some(i) => {
+                let ([x0, y0], [x1, y1]) = (points[i - 1], points[i]);
+                y0 + (y1 - y0) * (percent - x0) / (x1 - x0)
+            },
             None => {
-                let (x, y) = (x[BEZIER_SEGMENTS - 1], y[BEZIER_SEGMENTS - 1]);
+                let [x, y] = points[BEZIER_SEGMENTS - 1];
                 y + (1f32 - y) * (percent - x) / (1f32 - x)
             }
         }
     }
 }
 
+/// How bezier curves are evaluated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CurveMode {
+    /// curves are approximated by 10 linear segments, as in Spine runtimes
+    Approximate,
+    /// curves are solved exactly, slower but closer to the editor on slow, extreme curves
+    Exact,
+}
+
+/// Interpolation from a keyframe to the next one
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyCurve {
+    /// linear interpolation
+    Linear,
+    /// keeps the keyframe value until the next keyframe
+    Stepped,
+    /// bezier curve, with control points `[cx1, cy1, cx2, cy2]`
+    Bezier([f32; 4]),
+}
+
+/// Raw keyframe of a timeline
+#[derive(Debug, Clone)]
+pub struct Keyframe<'a, T: 'a> {
+    /// time, in seconds
+    pub time: f32,
+    /// value
+    pub value: &'a T,
+    /// interpolation to the next keyframe
+    pub curve: KeyCurve,
+}
+
+/// Iterator over the keyframes of a timeline
+pub struct Keyframes<'a, T: 'a> {
+    iter: Iter<'a, CurveTimeline<T>>
+}
+
+impl<'a, T> Iterator for Keyframes<'a, T> {
+    type Item = Keyframe<'a, T>;
+    fn next(&mut self) -> Option<Keyframe<'a, T>> {
+        self.iter.next().map(|t| Keyframe {
+            time: t.time,
+            value: &t.value,
+            curve: match t.curve {
+                json::TimelineCurve::CurveLinear => KeyCurve::Linear,
+                json::TimelineCurve::CurveStepped => KeyCurve::Stepped,
+                json::TimelineCurve::CurveBezier(ref p) => KeyCurve::Bezier([p[0], p[1], p[2], p[3]])
+            }
+        })
+    }
+}
+
+/// Gets the part of a curve between `from` and `to` percents of time, rescaled to `[0, 1]`
+fn cut_curve(curve: &json::TimelineCurve, from: f32, to: f32) -> json::TimelineCurve {
+    let p = match *curve {
+        json::TimelineCurve::CurveBezier(ref p) if from > 0.0 || to < 1.0 =>
+            [[0.0, 0.0], [p[0], p[1]], [p[2], p[3]], [1.0, 1.0]],
+        ref curve => return curve.clone()
+    };
+
+    let (ta, tb) = (bezier_param(&p, from), bezier_param(&p, to));
+    let left = split_bezier(&p, tb).0;
+    let q = if tb > 0.0 { split_bezier(&left, ta / tb).1 } else { left };
+
+    let (dx, dy) = (q[3][0] - q[0][0], q[3][1] - q[0][1]);
+    if dx.abs() < 1e-6 || dy.abs() < 1e-6 {
+        return json::TimelineCurve::CurveLinear;
+    }
+    json::TimelineCurve::CurveBezier(vec![(q[1][0] - q[0][0]) / dx, (q[1][1] - q[0][1]) / dy,
+                                          (q[2][0] - q[0][0]) / dx, (q[2][1] - q[0][1]) / dy])
+}
+
+/// Gets the point of a cubic bezier curve at parameter `t`
+fn bezier_point(p: &[[f32; 2]; 4], t: f32) -> [f32; 2] {
+    let u = 1.0 - t;
+    let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+    [a * p[0][0] + b * p[1][0] + c * p[2][0] + d * p[3][0],
+     a * p[0][1] + b * p[1][1] + c * p[2][1] + d * p[3][1]]
+}
+
+/// Gets the parameter of a cubic bezier curve at a given x, curves are monotonic on x
+fn bezier_param(p: &[[f32; 2]; 4], x: f32) -> f32 {
+    let (mut low, mut high) = (0f32, 1f32);
+    for _ in 0..30 {
+        let t = (low + high) / 2.0;
+        if bezier_point(p, t)[0] < x { low = t; } else { high = t; }
+    }
+    (low + high) / 2.0
+}
+
+/// Splits a cubic bezier curve at parameter `t` (de Casteljau)
+fn split_bezier(p: &[[f32; 2]; 4], t: f32) -> ([[f32; 2]; 4], [[f32; 2]; 4]) {
+    let lerp = |a: [f32; 2], b: [f32; 2]| [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t];
+    let (p01, p12, p23) = (lerp(p[0], p[1]), lerp(p[1], p[2]), lerp(p[2], p[3]));
+    let (p012, p123) = (lerp(p01, p12), lerp(p12, p23));
+    let p0123 = lerp(p012, p123);
+    ([p[0], p01, p012, p0123], [p0123, p123, p23, p[3]])
+}
+
 /// Set of timelines
+#[derive(Clone)]
 struct CurveTimelines<T> {
-    timelines: Vec<CurveTimeline<T>>
+    timelines: SmallVec<[CurveTimeline<T>; INLINE_KEYFRAMES]>,
+    /// bezier curves interpolations points of all the keyframes
+    samples: Box<[[f32; 2]]>,
+    mode: CurveMode
 }
 
 impl<T: Interpolate + Clone> CurveTimelines<T> {
@@ -176,13 +342,14 @@ impl<T: Interpolate + Clone> CurveTimelines<T> {
     fn from_json_vec<U: Curve<T>> (jtimelines: Option<Vec<U>>) -> Result<CurveTimelines<T>, SkeletonError>
     {
     	match jtimelines {
-    	    None => Ok(CurveTimelines { timelines: Vec::new() }),
+    	    None => Ok(CurveTimelines { timelines: SmallVec::new(), samples: Box::new([]), mode: CurveMode::Approximate }),
     	    Some(timelines) => {
-    	        let mut curves = Vec::with_capacity(timelines.len());
+    	        let mut curves = SmallVec::with_capacity(timelines.len());
+    	        let mut samples = Vec::new();
     	        for t in timelines.into_iter() {
     	            let value = try!(t.value());
     	            let curve = t.curve();
-    	            let points = CurveTimeline::<T>::compute_points(&curve);
+    	            let points = CurveTimeline::<T>::compute_points(&curve, &mut samples);
     	            curves.push(CurveTimeline {
     	                time: t.time(),
                         curve: curve,
@@ -190,27 +357,102 @@ impl<T: Interpolate + Clone> CurveTimelines<T> {
                         points: points
     	            });
     	        }
-    	        Ok(CurveTimelines { timelines: curves })
+    	        Ok(CurveTimelines {
+    	            timelines: curves,
+    	            samples: samples.into_boxed_slice(),
+    	            mode: CurveMode::Approximate
+    	        })
     	    }
     	}
     }
 
+    /// keeps the keyframes in `[start, end]`, shifted to start at 0, with keyframes sampled on
+    /// both boundaries
+    fn clip(&self, start: f32, end: f32) -> CurveTimelines<T> {
+        let mut samples = Vec::new();
+        let mut key = |time: f32, curve: json::TimelineCurve, value: T| CurveTimeline {
+            time: time,
+            points: CurveTimeline::<T>::compute_points(&curve, &mut samples),
+            curve: curve,
+            value: value
+        };
+
+        // curve of a keyframe from `from` percent of its interval, cut at `end`
+        let cut = |t: &CurveTimeline<T>, next: Option<&CurveTimeline<T>>, from: f32| {
+            let to = next.map(|n| ((end - t.time) / (n.time - t.time)).min(1.0)).unwrap_or(1.0);
+            cut_curve(&t.curve, from, to)
+        };
+
+        let mut timelines = SmallVec::new();
+        let first = self.timelines.iter().position(|t| t.time > start).unwrap_or(self.timelines.len());
+        if let Some(value) = self.interpolate(start) {
+            let t = &self.timelines[first - 1];
+            let next = self.timelines.get(first);
+            let from = next.map(|n| (start - t.time) / (n.time - t.time)).unwrap_or(0.0);
+            timelines.push(key(0.0, cut(t, next, from), value));
+        }
+        for (i, t) in self.timelines.iter().enumerate().skip(first).take_while(|&(_, t)| t.time < end) {
+            timelines.push(key(t.time - start, cut(t, self.timelines.get(i + 1), 0.0), t.value.clone()));
+        }
+        if let Some(value) = self.interpolate(end) {
+            if end > start {
+                timelines.push(key(end - start, json::TimelineCurve::CurveLinear, value));
+            }
+        }
+        CurveTimelines { timelines: timelines, samples: samples.into_boxed_slice(), mode: self.mode }
+    }
+
+    /// iterates over the keyframes
+    fn keys(&self) -> Keyframes<T> {
+        Keyframes {
+            iter: self.timelines.iter()
+        }
+    }
+
     /// interpolates `value` in the interval containing elapsed
     fn interpolate(&self, elapsed: f32) -> Option<T> {
-    	if self.timelines.is_empty() || elapsed < self.timelines[0].time {
-    	    return None;
-    	}
+        self.interpolate_at(elapsed, key_position(&self.timelines, |t| t.time, elapsed))
+    }
+
+    /// moves a position given by `key_position` to elapsed
+    fn seek(&self, elapsed: f32, position: &mut usize) {
+        seek_position(&self.timelines, |t| t.time, elapsed, position)
+    }
 
-    	if let Some(w) = self.timelines.windows(2).find(|&w| elapsed < w[1].time) {
-    	    let percent = (elapsed - w[0].time) / (w[1].time - w[0].time);
-    	    let curve_percent = w[0].get_percent(percent);
-    	    Some(w[0].value.interpolate(&w[1].value, curve_percent))
+    /// interpolates `value` at elapsed, `position` being the number of keyframes before it
+    fn interpolate_at(&self, elapsed: f32, position: usize) -> Option<T> {
+    	if position == 0 {
+    	    None
+    	} else if position < self.timelines.len() {
+    	    let (from, to) = (&self.timelines[position - 1], &self.timelines[position]);
+    	    let percent = (elapsed - from.time) / (to.time - from.time);
+    	    let curve_percent = from.get_percent(percent, self.mode, &self.samples);
+    	    Some(from.value.interpolate(&to.value, curve_percent))
     	} else {
     	    Some(self.timelines[self.timelines.len() - 1].value.clone())
     	}
     }
 }
 
+impl CurveTimelines<f32> {
+
+    /// rewrites angle keyframes so that linear interpolation follows the shortest arc between
+    /// consecutive keyframes, or back in `[-180, 180]` so that it interpolates raw values
+    fn set_shortest_arc(&mut self, shortest: bool) {
+        let mut previous = None;
+        for t in &mut self.timelines {
+            let mut angle = t.value;
+            let reference = if shortest { previous.unwrap_or(0f32) } else { 0f32 };
+            while angle - reference > 180.0 { angle -= 360.0; }
+            while angle - reference < -180.0 { angle += 360.0; }
+            t.value = angle;
+            previous = Some(angle);
+        }
+    }
+}
+
+/// Translate, rotate and scale timelines of a bone in an animation
+#[derive(Clone)]
 pub struct BoneTimeline {
     translate: CurveTimelines<(f32, f32)>,
     rotate: CurveTimelines<f32>,
@@ -224,7 +466,8 @@ impl BoneTimeline {
         -> Result<BoneTimeline, skeleton::error::SkeletonError>
     {
         let translate = try!(CurveTimelines::from_json_vec(json.translate));
-        let rotate = try!(CurveTimelines::from_json_vec(json.rotate));
+        let mut rotate = try!(CurveTimelines::from_json_vec(json.rotate));
+        rotate.set_shortest_arc(true);
         let scale = try!(CurveTimelines::from_json_vec(json.scale));
         Ok(BoneTimeline {
             translate: translate,
@@ -233,6 +476,45 @@ impl BoneTimeline {
         })
     }
 
+    /// Gets the translation keyframes, as `(x, y)`
+    pub fn translate_keys(&self) -> Keyframes<(f32, f32)> {
+        self.translate.keys()
+    }
+
+    /// Gets the rotation keyframes, in degrees
+    ///
+    /// With shortest rotations, angles are unwrapped and may be out of `[-180, 180]`
+    pub fn rotate_keys(&self) -> Keyframes<f32> {
+        self.rotate.keys()
+    }
+
+    /// Gets the scale keyframes, as `(x, y)`
+    pub fn scale_keys(&self) -> Keyframes<(f32, f32)> {
+        self.scale.keys()
+    }
+
+    /// keeps the keyframes in `[start, end]`, shifted to start at 0
+    pub fn clip(&self, start: f32, end: f32) -> BoneTimeline {
+        BoneTimeline {
+            translate: self.translate.clip(start, end),
+            rotate: self.rotate.clip(start, end),
+            scale: self.scale.clip(start, end),
+        }
+    }
+
+    /// Sets how bezier curves are evaluated
+    pub fn set_curve_mode(&mut self, mode: CurveMode) {
+        self.translate.mode = mode;
+        self.rotate.mode = mode;
+        self.scale.mode = mode;
+    }
+
+    /// Sets whether rotations are interpolated along the shortest arc between keyframes, as in
+    /// the editor (default), or as raw angles in `[-180, 180]`
+    pub fn set_shortest_rotation(&mut self, shortest: bool) {
+        self.rotate.set_shortest_arc(shortest);
+    }
+
     /// evaluates the interpolations for elapsed time on all timelines and
     /// returns the corresponding srt
     pub fn srt(&self, elapsed: f32) -> skeleton::SRT {
@@ -241,36 +523,116 @@ impl BoneTimeline {
     	let (scale_x, scale_y) = self.scale.interpolate(elapsed).unwrap_or((1.0, 1.0));
     	skeleton::SRT::new(scale_x, scale_y, rotation, x, y)
     }
+
+    /// Moves a cursor on the timelines to elapsed time
+    pub fn seek(&self, elapsed: f32, cursor: &mut TimelineCursor) {
+        self.translate.seek(elapsed, &mut cursor.positions[0]);
+        self.rotate.seek(elapsed, &mut cursor.positions[1]);
+        self.scale.seek(elapsed, &mut cursor.positions[2]);
+    }
+
+    /// evaluates the srt at elapsed time as `srt`, with a cursor moved to elapsed time by `seek`
+    pub fn srt_at(&self, elapsed: f32, cursor: &TimelineCursor) -> skeleton::SRT {
+        let (x, y) = self.translate.interpolate_at(elapsed, cursor.positions[0]).unwrap_or((0f32, 0f32));
+        let rotation = self.rotate.interpolate_at(elapsed, cursor.positions[1]).unwrap_or(0f32);
+        let (scale_x, scale_y) = self.scale.interpolate_at(elapsed, cursor.positions[2]).unwrap_or((1.0, 1.0));
+        skeleton::SRT::new(scale_x, scale_y, rotation, x, y)
+    }
 }
 
+/// Attachment and color timelines of a slot in an animation
+#[derive(Clone)]
 pub struct SlotTimeline {
-    attachment: Vec<json::SlotAttachmentTimeline>,
-    color: CurveTimelines<[u8; 4]>,
+    attachment: Box<[json::SlotAttachmentTimeline]>,
+    color: CurveTimelines<[f32; 4]>,
 }
 
 impl SlotTimeline {
 
+    /// converts json data into SlotTimeline
     pub fn from_json(json: json::SlotTimeline) -> Result<SlotTimeline, SkeletonError> {
         let color = try!(CurveTimelines::from_json_vec(json.color));
         Ok(SlotTimeline {
-            attachment: json.attachment.unwrap_or(Vec::new()),
+            attachment: json.attachment.unwrap_or(Vec::new()).into_boxed_slice(),
             color: color
         })
     }
 
-    pub fn interpolate_color(&self, elapsed: f32) -> [u8; 4] {
-        self.color.interpolate(elapsed).unwrap_or([255, 255, 255, 255])
+    /// Gets the color keyframes, normalized in `[0, 1]`
+    pub fn color_keys(&self) -> Keyframes<[f32; 4]> {
+        self.color.keys()
+    }
+
+    /// Gets the attachment keyframes, as `(time, attachment name)`
+    pub fn attachment_keys(&self) -> Vec<(f32, Option<&str>)> {
+        self.attachment.iter().map(|t| (t.time, t.name.as_ref().map(|n| &**n))).collect()
+    }
+
+    /// keeps the keyframes in `[start, end]`, shifted to start at 0
+    pub fn clip(&self, start: f32, end: f32) -> SlotTimeline {
+        let mut attachment = Vec::new();
+        if let Some(name) = self.interpolate_attachment(start) {
+            attachment.push(json::SlotAttachmentTimeline {
+                time: 0.0,
+                name: name.map(|n| n.to_owned())
+            });
+        }
+        attachment.extend(self.attachment.iter().filter(|t| t.time > start && t.time <= end)
+            .map(|t| json::SlotAttachmentTimeline { time: t.time - start, name: t.name.clone() }));
+        SlotTimeline {
+            attachment: attachment.into_boxed_slice(),
+            color: self.color.clip(start, end)
+        }
+    }
+
+    /// Sets how bezier curves are evaluated
+    pub fn set_curve_mode(&mut self, mode: CurveMode) {
+        self.color.mode = mode;
     }
 
+    /// interpolates the color at elapsed time
+    pub fn interpolate_color(&self, elapsed: f32) -> [f32; 4] {
+        self.color.interpolate(elapsed).unwrap_or([1.0, 1.0, 1.0, 1.0])
+    }
+
+    /// interpolates the attachment name at elapsed time, `None` before the first keyframe
     pub fn interpolate_attachment(&self, elapsed: f32) -> Option<Option<&str>> {
-        if self.attachment.is_empty() || elapsed < self.attachment[0].time {
+        self.interpolate_attachment_key(elapsed).map(|(_, name)| name)
+    }
+
+    /// interpolates the attachment keyframe at elapsed time, as `(index in attachment_keys,
+    /// attachment name)`, `None` before the first keyframe
+    pub fn interpolate_attachment_key(&self, elapsed: f32) -> Option<(usize, Option<&str>)> {
+        let position = key_position(&self.attachment, |a| a.time, elapsed);
+        self.attachment_key_at(position)
+    }
+
+    fn attachment_key_at(&self, position: usize) -> Option<(usize, Option<&str>)> {
+        if position == 0 {
             None
         } else {
-            let pos = self.attachment.iter().position(|a| elapsed < a.time).unwrap_or(self.attachment.len());
-            Some(self.attachment[pos - 1].name.as_ref().map(|n| &**n))
+            Some((position - 1, self.attachment[position - 1].name.as_ref().map(|n| &**n)))
         }
     }
 
+    /// Moves a cursor on the timelines to elapsed time
+    pub fn seek(&self, elapsed: f32, cursor: &mut TimelineCursor) {
+        self.color.seek(elapsed, &mut cursor.positions[0]);
+        seek_position(&self.attachment, |a| a.time, elapsed, &mut cursor.positions[1]);
+    }
+
+    /// interpolates the color as `interpolate_color`, with a cursor moved to elapsed time by `seek`
+    pub fn interpolate_color_at(&self, elapsed: f32, cursor: &TimelineCursor) -> [f32; 4] {
+        self.color.interpolate_at(elapsed, cursor.positions[0]).unwrap_or([1.0, 1.0, 1.0, 1.0])
+    }
+
+    /// interpolates the attachment keyframe as `interpolate_attachment_key`, with a cursor moved
+    /// to the time by `seek`
+    pub fn interpolate_attachment_key_at(&self, cursor: &TimelineCursor) -> Option<(usize, Option<&str>)> {
+        self.attachment_key_at(cursor.positions[1])
+    }
+
+    /// gets the names of all the attachments of the timeline
     pub fn get_attachment_names(&self) -> Vec<&str> {
         self.attachment.iter()
             .filter_map(|t| t.name.as_ref().map(|n| &**n)).collect()
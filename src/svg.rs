@@ -0,0 +1,126 @@
+//! Module to export animations as svg documents
+//!
+//! Each sprite is drawn as a flat colored quad. Svg y axis goes down, so points are flipped
+//! vertically unless the skeleton already uses `CoordinateSystem::YDown`.
+
+use skeleton::{CoordinateSystem, Skeleton};
+use skeleton::animation::{SkinAnimation, Sprite, Sprites};
+use std::f32;
+use std::io::{self, Write};
+
+/// Svg writer for the sprites of a skeleton
+pub struct SvgWriter<'a> {
+    skeleton: &'a Skeleton
+}
+
+impl<'a> SvgWriter<'a> {
+
+    /// Creates a new writer for the sprites of a skeleton
+    pub fn new(skeleton: &'a Skeleton) -> SvgWriter<'a> {
+        SvgWriter {
+            skeleton: skeleton
+        }
+    }
+
+    /// Gets the svg points of the world corners of a sprite
+    fn points(&self, sprite: &Sprite) -> [[f32; 2]; 4] {
+        let mut points = sprite.positions;
+        if self.skeleton.get_coordinate_system() == CoordinateSystem::YUp {
+            for point in &mut points {
+                point[1] = -point[1];
+            }
+        }
+        points
+    }
+
+    /// Writes the sprites of a single frame
+    pub fn write_frame<W: Write>(&self, writer: &mut W, sprites: Sprites) -> io::Result<()> {
+        let quads: Vec<_> = sprites.map(|s| (self.points(&s), s.color)).collect();
+
+        try!(write_header(writer, quads.iter().map(|&(ref p, _)| p)));
+        for &(ref points, color) in &quads {
+            try!(writeln!(writer, r#"  <polygon points="{}" fill="{}" fill-opacity="{}"/>"#,
+                          format_points(points), format_color(color), opacity(color)));
+        }
+        writeln!(writer, "</svg>")
+    }
+
+    /// Writes a whole animation, sampled every `delta` seconds, as SMIL keyframes
+    pub fn write_animation<W: Write>(&self, writer: &mut W, animation: &SkinAnimation, delta: f32)
+        -> io::Result<()>
+    {
+        // frames, per slot
+        let mut slots: Vec<Vec<Option<([[f32; 2]; 4], [u8; 4])>>> = Vec::new();
+        for sprites in animation.run(delta) {
+            for (i, sprite) in sprites.slots().enumerate() {
+                if slots.len() <= i {
+                    slots.push(Vec::new());
+                }
+                slots[i].push(sprite.map(|s| (self.points(&s), s.color)));
+            }
+        }
+
+        try!(write_header(writer, slots.iter().flat_map(|f| f.iter().filter_map(|f| f.as_ref()))
+                                                .map(|&(ref p, _)| p)));
+
+        let duration = delta * slots.get(0).map(|f| f.len()).unwrap_or(0) as f32;
+        for frames in slots.iter().filter(|f| f.iter().any(|f| f.is_some())) {
+
+            // hidden frames keep the last known quad but are fully transparent
+            let mut last = frames.iter().filter_map(|f| f.as_ref()).next().unwrap().0;
+            let (mut points, mut fills, mut opacities) = (Vec::new(), Vec::new(), Vec::new());
+            for frame in frames {
+                match *frame {
+                    Some((p, color)) => {
+                        last = p;
+                        fills.push(format_color(color));
+                        opacities.push(opacity(color).to_string());
+                    },
+                    None => {
+                        fills.push(format_color([0, 0, 0, 0]));
+                        opacities.push("0".to_owned());
+                    }
+                }
+                points.push(format_points(&last));
+            }
+
+            try!(writeln!(writer, r#"  <polygon points="{}">"#, points[0]));
+            for &(attribute, ref values) in &[("points", points), ("fill", fills),
+                                              ("fill-opacity", opacities)] {
+                try!(writeln!(writer, r#"    <animate attributeName="{}" values="{}" dur="{}s" calcMode="discrete" repeatCount="indefinite"/>"#,
+                              attribute, values.join(";"), duration));
+            }
+            try!(writeln!(writer, "  </polygon>"));
+        }
+        writeln!(writer, "</svg>")
+    }
+}
+
+/// Writes svg header, with a view box containing all points
+fn write_header<'a, W: Write, I: Iterator<Item=&'a [[f32; 2]; 4]>>(writer: &mut W, quads: I)
+    -> io::Result<()>
+{
+    let (mut min, mut max) = ([f32::MAX; 2], [f32::MIN; 2]);
+    for p in quads.flat_map(|q| q.iter()) {
+        min = [min[0].min(p[0]), min[1].min(p[1])];
+        max = [max[0].max(p[0]), max[1].max(p[1])];
+    }
+    if min[0] > max[0] {
+        min = [0.0, 0.0];
+        max = [0.0, 0.0];
+    }
+    writeln!(writer, r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+             min[0], min[1], max[0] - min[0], max[1] - min[1])
+}
+
+fn format_points(points: &[[f32; 2]; 4]) -> String {
+    points.iter().map(|p| format!("{},{}", p[0], p[1])).collect::<Vec<_>>().join(" ")
+}
+
+fn format_color(color: [u8; 4]) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+fn opacity(color: [u8; 4]) -> f32 {
+    color[3] as f32 / 255.0
+}
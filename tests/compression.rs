@@ -0,0 +1,45 @@
+//! Loads compressed documents, `cargo test --features compression --test compression`
+
+#![cfg(feature = "compression")]
+
+extern crate flate2;
+extern crate ruzstd;
+extern crate spine;
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use ruzstd::encoding::{compress_to_vec, CompressionLevel};
+use spine::atlas::Atlas;
+use spine::skeleton::Skeleton;
+use std::io::Write;
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[test]
+fn compressed_skeleton() {
+    let src: &[u8] = include_bytes!("example.json");
+    let zstd = compress_to_vec(src, CompressionLevel::Fastest);
+    for data in &[gzip(src), zstd, src.to_vec()] {
+        let doc = Skeleton::from_compressed_reader(&data[..]).unwrap();
+        assert!(doc.get_animated_skin("default", Some("walk")).is_ok());
+    }
+
+    let mut truncated = gzip(src);
+    truncated.truncate(40);
+    assert!(Skeleton::from_compressed_reader(&truncated[..]).is_err());
+}
+
+#[test]
+fn compressed_atlas() {
+    let src: &[u8] = include_bytes!("example.atlas");
+    let expected = Atlas::from_reader(src).unwrap().count();
+    let zstd = compress_to_vec(src, CompressionLevel::Fastest);
+    for data in &[gzip(src), zstd] {
+        let atlas = Atlas::from_compressed_reader(&data[..]).unwrap();
+        assert_eq!(atlas.count(), expected);
+    }
+}
@@ -0,0 +1,128 @@
+//! Runs the parser and all animations over a directory of Spine exports
+//!
+//! `SPINE_CORPUS_DIR=path/to/exports cargo test --features corpus --test corpus -- --nocapture`
+
+#![cfg(feature = "corpus")]
+
+extern crate spine;
+extern crate serde_json;
+
+use serde_json::Value;
+use std::env;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+const SUPPORTED_ROOT: &'static [&'static str] = &[
+    "skeleton", "bones", "slots", "skins", "events", "animations"];
+const SUPPORTED_ATTACHMENTS: &'static [&'static str] = &["region", "regionsequence", "boundingbox"];
+const SUPPORTED_ANIMATION: &'static [&'static str] = &["bones", "slots", "events"];
+const SUPPORTED_BONE_TIMELINES: &'static [&'static str] = &["translate", "rotate", "scale"];
+const SUPPORTED_SLOT_TIMELINES: &'static [&'static str] = &["attachment", "color"];
+
+/// Lists the features of a document which are not handled by this crate
+fn unsupported_features(doc: &Value) -> Vec<String> {
+    let mut features = Vec::new();
+    {
+        let mut check = |context: &str, json: Option<&Value>, supported: &[&str]| {
+            for key in json.and_then(|j| j.as_object()).into_iter().flat_map(|o| o.keys()) {
+                if !supported.contains(&&**key) {
+                    let feature = format!("{}{}", context, key);
+                    if !features.contains(&feature) {
+                        features.push(feature);
+                    }
+                }
+            }
+        };
+
+        check("", Some(doc), SUPPORTED_ROOT);
+
+        for animation in doc.get("animations").and_then(|a| a.as_object()).into_iter().flat_map(|a| a.values()) {
+            check("animation ", Some(animation), SUPPORTED_ANIMATION);
+            for bone in animation.get("bones").and_then(|b| b.as_object()).into_iter().flat_map(|b| b.values()) {
+                check("bone timeline ", Some(bone), SUPPORTED_BONE_TIMELINES);
+            }
+            for slot in animation.get("slots").and_then(|s| s.as_object()).into_iter().flat_map(|s| s.values()) {
+                check("slot timeline ", Some(slot), SUPPORTED_SLOT_TIMELINES);
+            }
+        }
+    }
+
+    let attachments = doc.get("skins").and_then(|s| s.as_object()).into_iter()
+        .flat_map(|skins| skins.values())
+        .filter_map(|skin| skin.as_object()).flat_map(|slots| slots.values())
+        .filter_map(|slot| slot.as_object()).flat_map(|attachments| attachments.values());
+    for attachment in attachments {
+        if let Some(type_) = attachment.get("type").and_then(|t| t.as_str()) {
+            let feature = format!("attachment {}", type_);
+            if !SUPPORTED_ATTACHMENTS.contains(&type_) && !features.contains(&feature) {
+                features.push(feature);
+            }
+        }
+    }
+
+    features
+}
+
+/// Loads a document and samples all animations of all skins
+fn run(content: &[u8]) -> Result<(), String> {
+    let doc = try!(spine::skeleton::Skeleton::from_reader(content).map_err(|e| e.to_string()));
+    for skin in doc.get_skins_names() {
+        for animation in doc.get_animations_names() {
+            let anim = try!(doc.get_animated_skin(skin, Some(animation)).map_err(|e| e.to_string()));
+            for sprites in anim.run(1.0 / 30.0) {
+                for _ in sprites {}
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn corpus() {
+    let dir = env::var("SPINE_CORPUS_DIR").unwrap_or("tests/corpus".to_owned());
+    let entries = match fs::read_dir(&Path::new(&dir)) {
+        Ok(entries) => entries,
+        Err(_) => {
+            println!("no corpus found in '{}'", dir);
+            return;
+        }
+    };
+
+    let mut failures = Vec::new();
+    for entry in entries {
+        let path = entry.unwrap().path();
+        if path.extension().map_or(true, |e| e != "json") {
+            continue;
+        }
+
+        let mut content = Vec::new();
+        File::open(&path).unwrap().read_to_end(&mut content).unwrap();
+
+        let features = match serde_json::from_slice::<Value>(&content) {
+            Ok(json) => unsupported_features(&json),
+            Err(e) => {
+                println!("{}: invalid json: {}", path.display(), e);
+                failures.push(path);
+                continue;
+            }
+        };
+
+        match run(&content) {
+            Ok(()) => println!("{}: ok", path.display()),
+            Err(e) => {
+                println!("{}: failed: {}", path.display(), e);
+
+                // only fail if nothing explains the failure
+                if features.is_empty() {
+                    failures.push(path.clone());
+                }
+            }
+        }
+        if !features.is_empty() {
+            println!("{}: unsupported features: {}", path.display(), features.join(", "));
+        }
+    }
+
+    assert!(failures.is_empty(), "failed to run {:?}", failures);
+}
@@ -0,0 +1,58 @@
+//! Exports animations as svg documents, `cargo test --features svg --test svg`
+
+#![cfg(feature = "svg")]
+
+extern crate spine;
+
+use spine::skeleton::{CoordinateSystem, Skeleton};
+use spine::svg::SvgWriter;
+
+#[test]
+fn svg_frame() {
+    let doc = Skeleton::from_file("tests/events.json").unwrap();
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+    let writer = SvgWriter::new(&doc);
+
+    let mut svg = Vec::new();
+    writer.write_frame(&mut svg, anim.interpolate(0.0).unwrap()).unwrap();
+    let svg = String::from_utf8(svg).unwrap();
+
+    // the 20x40 body quad around the bone at y = 10, flipped vertically
+    assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-10 -30 20 40">"#));
+    assert_eq!(svg.matches("<polygon ").count(), 1);
+    assert!(svg.contains(r##"points="-10,-30 10,-30 10,10 -10,10" fill="#ff8000""##));
+    assert!(svg.trim_end().ends_with("</svg>"));
+}
+
+#[test]
+fn svg_frame_y_down() {
+    let mut doc = Skeleton::from_file("tests/events.json").unwrap();
+    doc.set_coordinate_system(CoordinateSystem::YDown);
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+    let writer = SvgWriter::new(&doc);
+
+    // sprites are already y-down, the svg is the same as for a y-up skeleton
+    let mut svg = Vec::new();
+    writer.write_frame(&mut svg, anim.interpolate(0.0).unwrap()).unwrap();
+    let svg = String::from_utf8(svg).unwrap();
+    assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-10 -30 20 40">"#));
+    assert!(svg.contains(r##"points="-10,-30 10,-30 10,10 -10,10" fill="#ff8000""##));
+}
+
+#[test]
+fn svg_animation() {
+    let doc = Skeleton::from_file("tests/events.json").unwrap();
+    let anim = doc.get_animated_skin("default", Some("fade")).unwrap();
+    let writer = SvgWriter::new(&doc);
+
+    let mut svg = Vec::new();
+    writer.write_animation(&mut svg, &anim, 0.25).unwrap();
+    let svg = String::from_utf8(svg).unwrap();
+
+    // frames at 0, 0.25 and 0.5, each one lasting 0.25 seconds
+    assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-10 -30 20 40">"#));
+    assert_eq!(svg.matches("<polygon ").count(), 1);
+    assert_eq!(svg.matches(r#"dur="0.75s""#).count(), 3);
+    assert!(svg.contains(r##"<animate attributeName="fill" values="#ff8000;#ff8000;#ff8000""##));
+    assert!(svg.contains(r#"<animate attributeName="fill-opacity" values="1;0.5019608;0""#));
+}
@@ -1,3 +1,4 @@
+extern crate serde_json;
 extern crate spine;
 
 use std::io::BufReader;
@@ -9,13 +10,8 @@ fn animations_names() {
 
     let names = doc.get_animations_names();
 
-    assert!(names.get(0).unwrap() == &"walk" ||
-        names.get(0).unwrap() == &"jump");
-    assert!(names.get(1).unwrap() == &"walk" ||
-        names.get(1).unwrap() == &"jump");
-
-    assert!(names.contains(&"walk"));
-    assert!(names.contains(&"jump"));
+    // document order
+    assert_eq!(names, ["walk", "jump"]);
     assert!(!names.contains(&"crawl"));
 }
 
@@ -47,3 +43,1938 @@ fn attachement_names() {
         "right-hand", "right-lower-leg", "right-shoulder", "right-upper-leg", "torso"
     ]);
 }
+
+#[test]
+fn mix_animations() {
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+    let jump = doc.get_animated_skin("default", Some("jump")).unwrap();
+
+    // alpha = 0 is the first animation, alpha = 1 is the second one
+    for (alpha, anim, time) in vec![(0.0, &walk, 0.2), (1.0, &jump, 0.5)] {
+        let mixed = walk.mix(&jump, alpha, 0.2, 0.5).unwrap();
+        let sprites = anim.interpolate(time).unwrap();
+        for (m, s) in mixed.zip(sprites) {
+            assert_eq!(m.attachment, s.attachment);
+            assert_eq!(m.color, s.color);
+            assert!((m.srt.position[0] - s.srt.position[0]).abs() < 1e-3);
+            assert!((m.srt.position[1] - s.srt.position[1]).abs() < 1e-3);
+            assert!((m.srt.rotation - s.srt.rotation).abs() < 1e-3);
+        }
+    }
+
+    assert!(walk.mix(&jump, 0.5, walk.get_duration() + 1.0, 0.0).is_none());
+}
+
+#[test]
+fn sprites_delta() {
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+
+    // nothing changes at the same time
+    assert_eq!(walk.delta(0.2, 0.2, 0.0).unwrap().count(), 0);
+
+    let deltas: Vec<_> = walk.delta(0.2, 0.3, 1e-3).unwrap().collect();
+    assert!(deltas.len() > 0);
+    assert!(deltas.iter().all(|d| d.sprite.is_some()));
+
+    assert!(walk.delta(0.0, walk.get_duration() + 1.0, 0.0).is_none());
+}
+
+#[test]
+fn scene_adapter() {
+    use spine::skeleton::animation::Sprite;
+    use spine::skeleton::scene::{SceneAdapter, SceneGraph};
+
+    struct Counter { created: usize, updated: usize, removed: usize }
+    impl SceneGraph for Counter {
+        type Node = usize;
+        fn create_node(&mut self, slot_index: usize, _: &Sprite) -> usize {
+            self.created += 1;
+            slot_index
+        }
+        fn update_node(&mut self, _: &mut usize, _: &Sprite) { self.updated += 1; }
+        fn remove_node(&mut self, _: usize) { self.removed += 1; }
+    }
+
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+
+    let mut graph = Counter { created: 0, updated: 0, removed: 0 };
+    let mut adapter = SceneAdapter::new();
+    adapter.update(&mut graph, walk.interpolate(0.0).unwrap());
+    let visible = walk.interpolate(0.0).unwrap().count();
+    assert_eq!(graph.created, visible);
+    assert_eq!(adapter.get_node(0), Some(&0));
+
+    adapter.update(&mut graph, walk.interpolate(0.1).unwrap());
+    assert_eq!(graph.created, visible);
+    assert_eq!(graph.updated, visible);
+
+    adapter.clear(&mut graph);
+    assert_eq!(graph.removed, visible);
+    assert!(adapter.get_node(0).is_none());
+}
+
+#[test]
+fn audio_events() {
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let info = doc.get_animation_info("step").unwrap();
+
+    let events = info.audio_events(0.1, 1000);
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].audio, "sounds/footstep.ogg");
+    assert_eq!(events[0].volume, 0.5);
+    assert_eq!(events[0].schedule_sample, 150);
+    assert_eq!(events[1].balance, -1.0);
+    assert_eq!(events[1].schedule_sample, 650);
+
+    assert!(doc.get_animation_info("crawl").is_err());
+}
+
+#[test]
+fn reverse_playback() {
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+
+    let last = walk.interpolate(walk.get_duration()).unwrap().next().unwrap();
+    let first = walk.run(0.1).speed(-1.0).next().unwrap().next().unwrap();
+    assert_eq!(first.srt.position, last.srt.position);
+
+    assert_eq!(walk.run(0.1).speed(2.0).count(), walk.run(0.2).count());
+}
+
+#[test]
+fn sprite_material() {
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    assert_eq!(doc.get_attachments_names(), ["armor", "body"]);
+
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+    let sprite = anim.interpolate(0.0).unwrap().next().unwrap();
+    assert_eq!(sprite.attachment, "body");
+    assert_eq!(sprite.material, Some("glow"));
+}
+
+#[test]
+fn events_between() {
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+
+    let names = |from, to| anim.events_between(from, to).map(|e| e.name).collect::<Vec<_>>();
+    assert_eq!(names(0.0, 0.25), ["footstep"]);
+    assert_eq!(names(0.25, 0.5), ["hit"]);
+    assert!(names(0.3, 0.4).is_empty());
+
+    // looping
+    assert_eq!(names(0.6, 0.3), ["footstep", "footstep"]);
+
+    // values default to the event definition
+    assert_eq!(anim.events_between(0.4, 0.5).next().unwrap().int, 3);
+}
+
+#[test]
+fn procedural_pose() {
+    use spine::skeleton::pose::{Pose, PoseMode};
+
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let anim = doc.get_animated_skin("default", None).unwrap();
+
+    let mut pose = Pose::new(&doc);
+    pose.set_bone_translation("body", 5.0, 0.0, PoseMode::Relative).unwrap();
+    pose.set_bone_rotation("body", 90.0, PoseMode::Absolute).unwrap();
+    assert!(pose.set_bone_scale("tail", 2.0, 2.0, PoseMode::Absolute).is_err());
+
+    let sprite = anim.interpolate_with_pose(0.0, &pose).unwrap().next().unwrap();
+    assert_eq!(sprite.srt.position, [5.0, 10.0]);
+    assert!((sprite.srt.rotation - std::f32::consts::PI / 2.0).abs() < 1e-5);
+
+    pose.clear();
+    let sprite = anim.interpolate_with_pose(0.0, &pose).unwrap().next().unwrap();
+    assert_eq!(sprite.srt.position, [0.0, 10.0]);
+}
+
+#[test]
+fn shared_skin_animation() {
+    use spine::skeleton::animation::SkinAnimation;
+    use std::sync::Arc;
+    use std::thread;
+
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = Arc::new(spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap());
+    let expected: Vec<_> = doc.get_animated_skin("default", Some("walk")).unwrap()
+        .interpolate(0.3).unwrap().map(|s| s.srt.position).collect();
+
+    let walk = SkinAnimation::from_arc(doc.clone(), "default", Some("walk")).unwrap();
+    assert!(SkinAnimation::from_arc(doc.clone(), "default", Some("crawl")).is_err());
+    let positions = thread::spawn(move || {
+        walk.interpolate(0.3).unwrap().map(|s| s.srt.position).collect::<Vec<_>>()
+    }).join().unwrap();
+    assert_eq!(positions, expected);
+}
+
+#[test]
+fn timeline_cursors() {
+    use spine::skeleton::TimelineCursor;
+    use spine::skeleton::animation::SkeletonInstance;
+    use std::sync::Arc;
+
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = Arc::new(spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap());
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+    let positions = |time| walk.interpolate(time).unwrap().map(|s| s.srt.position).collect::<Vec<_>>();
+
+    let mut instance = SkeletonInstance::new(doc.clone(), "default").unwrap();
+    instance.set_animation(Some("walk")).unwrap();
+    let sprites = |instance: &SkeletonInstance| instance.interpolate().unwrap().map(|s| s.srt.position).collect::<Vec<_>>();
+    for _ in 0..20 {
+        instance.advance(0.05);
+        assert_eq!(sprites(&instance), positions(instance.get_time()));
+    }
+    for &time in &[0.2, 0.9, 0.0, 0.55] {
+        instance.set_time(time);
+        assert_eq!(sprites(&instance), positions(time));
+    }
+
+    let info = doc.get_animation_info("walk").unwrap();
+    let timeline = info.bone_timeline("left upper leg").unwrap().unwrap();
+    let mut cursor = TimelineCursor::default();
+    for &time in &[0.1, 0.4, 0.2, 0.8] {
+        timeline.seek(time, &mut cursor);
+        assert_eq!(timeline.srt_at(time, &cursor).rotation, timeline.srt(time).rotation);
+    }
+}
+
+#[test]
+fn eval_context() {
+    use spine::skeleton::animation::EvalContext;
+
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let src: &[u8] = include_bytes!("hitbox.json");
+    let goblin = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+
+    // the same context is reused by skeletons of different sizes
+    let mut context = EvalContext::new();
+    let animations = [doc.get_animated_skin("default", Some("walk")).unwrap(),
+                      doc.get_animated_skin("default", Some("jump")).unwrap(),
+                      goblin.get_animated_skin("goblin", Some("move")).unwrap()];
+    for anim in animations.iter() {
+        for &time in &[0.0, 0.3, 0.7] {
+            let expected: Vec<_> = anim.interpolate(time).unwrap().map(|s| (s.attachment, s.positions, s.color)).collect();
+            let sprites: Vec<_> = anim.interpolate_in(time, &mut context).unwrap()
+                .map(|s| (s.attachment, s.positions, s.color)).collect();
+            assert_eq!(sprites, expected);
+        }
+        assert!(anim.interpolate_in(anim.get_duration() + 1.0, &mut context).is_none());
+    }
+}
+
+#[test]
+fn skeleton_instances() {
+    use spine::skeleton::animation::SkeletonInstance;
+    use std::sync::Arc;
+
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = Arc::new(spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap());
+
+    let mut walking = SkeletonInstance::new(doc.clone(), "default").unwrap();
+    walking.set_animation(Some("walk")).unwrap();
+    walking.advance(0.3);
+    let mut jumping = SkeletonInstance::new(doc.clone(), "default").unwrap();
+    jumping.set_animation(Some("jump")).unwrap();
+    assert!(jumping.set_animation(Some("crawl")).is_err());
+
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+    for (a, b) in walking.interpolate().unwrap().zip(walk.interpolate(0.3).unwrap()) {
+        assert_eq!(a.attachment, b.attachment);
+        assert_eq!(a.srt.position, b.srt.position);
+    }
+
+    walking.set_time(walking.get_duration() + 1.0);
+    assert!(walking.interpolate().is_none());
+    assert!(jumping.interpolate().is_some());
+}
+
+#[test]
+fn bone_world_srt() {
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+
+    let hand = walk.bone_world_srt("right hand", 0.3).unwrap();
+    let bones = walk.bones_world_srts(0.3).unwrap();
+    let &(_, ref srt) = bones.iter().find(|&&(name, _)| name == "right hand").unwrap();
+    assert_eq!(hand.position, srt.position);
+
+    assert!(walk.bone_world_srt("tail", 0.3).is_none());
+    assert!(walk.bone_world_srt("right hand", walk.get_duration() + 1.0).is_none());
+}
+
+#[test]
+fn attachment_overrides() {
+    use spine::skeleton::animation::SkeletonInstance;
+    use std::sync::Arc;
+
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = Arc::new(spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap());
+    let mut instance = SkeletonInstance::new(doc, "default").unwrap();
+
+    let count = instance.interpolate().unwrap().count();
+    instance.set_attachment("eyes", Some("eyes-closed")).unwrap();
+    assert!(instance.interpolate().unwrap().any(|s| s.attachment == "eyes-closed"));
+
+    instance.set_attachment("eyes", None).unwrap();
+    assert_eq!(instance.interpolate().unwrap().count(), count - 1);
+
+    assert!(instance.set_attachment("eyes", Some("sword")).is_err());
+    assert!(instance.set_attachment("tail", None).is_err());
+
+    instance.clear_attachment("eyes").unwrap();
+    assert_eq!(instance.interpolate().unwrap().count(), count);
+}
+
+#[test]
+fn composite_skin() {
+    let src: &[u8] = include_bytes!("events.json");
+    let mut doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+
+    doc.composite_skin("armored", &["default", "armor"]).unwrap();
+    assert!(doc.composite_skin("broken", &["default", "hat"]).is_err());
+
+    let anim = doc.get_animated_skin("armored", Some("step")).unwrap();
+    assert_eq!(anim.interpolate(0.0).unwrap().next().unwrap().attachment, "armor");
+}
+
+#[test]
+fn custom_skin() {
+    use spine::skeleton::{Attachment, SRT};
+
+    let src: &[u8] = include_bytes!("events.json");
+    let mut doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+
+    let cape = Attachment::new(Some("capes/red#cloth"), &SRT::new(1.0, 1.0, 0.0, 0.0, 0.0), 10.0, 10.0);
+    assert!(doc.new_skin(vec![("tail", "cape", cape.clone())]).is_err());
+    let skin = doc.new_skin(vec![("body", "body", cape)]).unwrap();
+    doc.add_skin("caped", skin);
+
+    let anim = doc.get_animated_skin("caped", None).unwrap();
+    let sprite = anim.interpolate(0.0).unwrap().next().unwrap();
+    assert_eq!(sprite.attachment, "capes/red");
+    assert_eq!(sprite.material, Some("cloth"));
+}
+
+#[test]
+fn bounds() {
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+
+    // 20x40 body on a bone at (0, 10)
+    assert_eq!(anim.bounds(0.0), Some([[-10.0, -10.0], [10.0, 30.0]]));
+    assert!(anim.bounds(anim.get_duration() + 1.0).is_none());
+}
+
+#[test]
+fn hit_test() {
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+
+    assert_eq!(anim.hit_test([5.0, 25.0], 0.0), Some(("body", "body")));
+    assert_eq!(anim.hit_test([15.0, 25.0], 0.0), None);
+}
+
+#[test]
+fn sprite_positions() {
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+
+    let sprite = anim.interpolate(0.0).unwrap().next().unwrap();
+    assert_eq!(sprite.positions, [[-10.0, 30.0], [10.0, 30.0], [10.0, -10.0], [-10.0, -10.0]]);
+}
+
+#[test]
+fn render_buffers() {
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+
+    let texture = spine::atlas::Texture {
+        name: "body".to_owned(),
+        rotate: spine::atlas::Rotation::None,
+        xy: (0, 0),
+        size: (20, 40),
+        orig: (20, 40),
+        offset: (0, 0),
+        index: -1,
+        split: None,
+        pad: None
+    };
+    let builder = spine::render::BufferBuilder::new(vec![texture], (40, 40));
+
+    let (mut vertices, mut indices) = (Vec::new(), Vec::new());
+    assert!(builder.fill(&anim, 0.0, &mut vertices, &mut indices));
+    assert_eq!(vertices.len(), 4);
+    assert_eq!(indices, vec![0, 1, 2, 0, 2, 3]);
+    assert_eq!(vertices[2].position, [10.0, -10.0]);
+    assert_eq!(vertices[2].uv, [0.5, 1.0]);
+
+    assert!(!builder.fill(&anim, anim.get_duration() + 1.0, &mut vertices, &mut indices));
+    assert!(vertices.is_empty() && indices.is_empty());
+}
+
+#[test]
+fn render_buffers_overflow() {
+    // one more quad than 16-bit indices can address
+    let count = 16385;
+    let slots: Vec<String> = (0..count)
+        .map(|i| format!(r#"{{ "name": "s{}", "bone": "root", "attachment": "body" }}"#, i)).collect();
+    let attachments: Vec<String> = (0..count)
+        .map(|i| format!(r#""s{}": {{ "body": {{ "width": 20, "height": 40 }} }}"#, i)).collect();
+    let src = format!(r#"{{ "bones": [{{ "name": "root" }}], "slots": [{}], "skins": {{ "default": {{ {} }} }} }}"#,
+                      slots.join(","), attachments.join(","));
+    let doc = spine::skeleton::Skeleton::from_slice(src.as_bytes()).unwrap();
+    let anim = doc.get_animated_skin("default", None).unwrap();
+
+    let texture = spine::atlas::Texture {
+        name: "body".to_owned(),
+        rotate: spine::atlas::Rotation::None,
+        xy: (0, 0),
+        size: (20, 40),
+        orig: (20, 40),
+        offset: (0, 0),
+        index: -1,
+        split: None,
+        pad: None
+    };
+    let builder = spine::render::BufferBuilder::new(vec![texture], (40, 40));
+
+    let (mut vertices, mut indices) = (Vec::new(), Vec::new());
+    assert!(!builder.fill(&anim, 0.0, &mut vertices, &mut indices));
+    assert_eq!(vertices.len(), 65536);
+    assert_eq!(indices.len(), 6 * 16384);
+    assert_eq!(*indices.last().unwrap(), 65535);
+}
+
+#[test]
+fn bind_atlas() {
+    let src: &[u8] = include_bytes!("events.json");
+    let mut doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+
+    // region rotated in a 40x40 page
+    doc.bind_atlas(vec![spine::atlas::Texture {
+        name: "body".to_owned(),
+        rotate: spine::atlas::Rotation::CW90,
+        xy: (0, 0),
+        size: (20, 40),
+        orig: (20, 40),
+        offset: (0, 0),
+        index: -1,
+        split: None,
+        pad: None
+    }], (40, 40));
+
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+    let sprite = anim.interpolate(0.0).unwrap().next().unwrap();
+    assert_eq!(sprite.uvs, Some([[0.0, 0.5], [0.0, 0.0], [1.0, 0.0], [1.0, 0.5]]));
+
+    // the top-left corner of the image is the bottom-left one of the rotated region
+    let vertices = sprite.vertices().unwrap();
+    assert_eq!(vertices[0], (sprite.positions[0], [0.0, 0.5]));
+    assert_eq!(vertices[2], (sprite.positions[2], [1.0, 0.0]));
+
+    // armor has no region in the atlas
+    let anim = doc.get_animated_skin("armor", Some("step")).unwrap();
+    assert!(anim.interpolate(0.0).unwrap().next().unwrap().uvs.is_none());
+}
+
+#[test]
+fn bind_trimmed_atlas() {
+    let src: &[u8] = include_bytes!("events.json");
+    let mut doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+
+    // the 20x40 image has been trimmed to its 10x20 top-right part
+    let texture = spine::atlas::Texture {
+        name: "body".to_owned(),
+        rotate: spine::atlas::Rotation::None,
+        xy: (0, 0),
+        size: (10, 20),
+        orig: (20, 40),
+        offset: (10, 20),
+        index: -1,
+        split: None,
+        pad: None
+    };
+    assert_eq!(texture.trim(), [[0.5, 0.5], [1.0, 1.0]]);
+
+    // unbound regions of a buffer builder are trimmed the same way
+    let positions = {
+        let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+        let region = spine::atlas::Texture { name: texture.name.clone(), ..texture };
+        let builder = spine::render::BufferBuilder::new(vec![region], (20, 20));
+        let (mut vertices, mut indices) = (Vec::new(), Vec::new());
+        builder.fill(&anim, 0.0, &mut vertices, &mut indices);
+        vertices.iter().map(|v| v.position).collect::<Vec<_>>()
+    };
+    assert_eq!(positions, [[0.0, 30.0], [10.0, 30.0], [10.0, 10.0], [0.0, 10.0]]);
+
+    doc.bind_atlas(vec![texture], (20, 20));
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+    let sprite = anim.interpolate(0.0).unwrap().next().unwrap();
+    assert_eq!(sprite.uvs, Some([[0.0, 0.0], [0.5, 0.0], [0.5, 1.0], [0.0, 1.0]]));
+    assert_eq!(sprite.positions, [[0.0, 30.0], [10.0, 30.0], [10.0, 10.0], [0.0, 10.0]]);
+}
+
+#[test]
+fn premultiplied_color() {
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+
+    let sprite = anim.interpolate(0.0).unwrap().next().unwrap();
+    assert_eq!(sprite.color, [255, 128, 0, 128]);
+    assert_eq!(sprite.color_premultiplied(), [128, 64, 0, 128]);
+}
+
+#[test]
+fn normalized_color() {
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+
+    let sprite = anim.interpolate(0.0).unwrap().next().unwrap();
+    assert_eq!(sprite.color_f32, [1.0, 128.0 / 255.0, 0.0, 128.0 / 255.0]);
+}
+
+#[test]
+fn animation_end() {
+    use spine::skeleton::animation::AnimationEnd;
+
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+    let rotation = |sprites: Option<spine::skeleton::animation::Sprites>| {
+        sprites.unwrap().next().unwrap().srt.rotation
+    };
+
+    // 0.75s animation (its last key is an event) sampled every 0.2s
+    assert_eq!(anim.get_duration(), 0.75);
+    assert_eq!(anim.run(0.2).count(), 4);
+    assert_eq!(anim.run(0.2).end(AnimationEnd::Stop).count(), 4);
+
+    let mut clamp = anim.run(0.2).end(AnimationEnd::Clamp).skip(4);
+    let last = rotation(anim.interpolate(0.75));
+    assert_eq!(rotation(clamp.next()), last);
+    assert_eq!(rotation(clamp.next()), last);
+
+    let mut looped = anim.run(0.2).end(AnimationEnd::Loop).skip(4);
+    assert!((rotation(looped.next()) - rotation(anim.interpolate(0.05))).abs() < 1e-5);
+    assert_eq!(anim.run(0.2).end(AnimationEnd::Loop).speed(-1.0).take(10).count(), 10);
+}
+
+#[test]
+fn interpolate_clamped() {
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+
+    assert!(anim.interpolate(1.0).is_none());
+    let last = anim.interpolate(0.5).unwrap().next().unwrap();
+    let clamped = anim.interpolate_clamped(1.0).next().unwrap();
+    assert_eq!(clamped.srt.rotation, last.srt.rotation);
+    let first = anim.interpolate(0.0).unwrap().next().unwrap();
+    assert_eq!(anim.interpolate_clamped(-1.0).next().unwrap().srt.rotation, first.srt.rotation);
+}
+
+#[test]
+fn sprites_len() {
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+
+    let sprites = walk.interpolate(0.3).unwrap();
+    let len = sprites.len();
+    assert_eq!(sprites.count(), len);
+
+    // the length follows the iteration
+    let mut sprites = walk.interpolate(0.3).unwrap();
+    for remaining in (0..len).rev() {
+        assert!(sprites.next().is_some());
+        assert_eq!(sprites.len(), remaining);
+    }
+    assert!(sprites.next().is_none());
+
+    let slots = walk.interpolate(0.3).unwrap().slots();
+    assert_eq!(slots.len(), walk.get_slots_count());
+    assert_eq!(slots.filter(|s| s.is_some()).count(), len);
+}
+
+#[test]
+fn blend_poses() {
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+    let jump = doc.get_animated_skin("default", Some("jump")).unwrap();
+    let (walk_pose, jump_pose) = (walk.sample(0.2).unwrap(), jump.sample(0.5).unwrap());
+
+    // blending sampled poses matches mixing animations
+    let pose = walk_pose.blend(&jump_pose, 0.3);
+    for (p, m) in walk.pose_sprites(&pose).zip(walk.mix(&jump, 0.3, 0.2, 0.5).unwrap()) {
+        assert_eq!(p.attachment, m.attachment);
+        assert_eq!(p.color, m.color);
+        assert!((p.srt.position[0] - m.srt.position[0]).abs() < 1e-3);
+        assert!((p.srt.position[1] - m.srt.position[1]).abs() < 1e-3);
+        assert!((p.srt.rotation - m.srt.rotation).abs() < 1e-3);
+    }
+
+    assert_eq!(walk.pose_sprites(&walk_pose).count(), walk.interpolate(0.2).unwrap().count());
+    assert!(walk.sample(walk.get_duration() + 1.0).is_none());
+}
+
+#[test]
+fn bone_mask() {
+    use spine::skeleton::mask::BoneMask;
+
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+
+    let mask = BoneMask::from_root(&doc, "torso").unwrap();
+    assert!(mask.contains_bone(8) && !mask.contains_bone(1));
+    assert!(BoneMask::from_root(&doc, "tail").is_err());
+
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+    let setup = doc.get_animated_skin("default", None).unwrap();
+    let upper = doc.get_animated_skin("default", Some("walk")).unwrap().with_mask(&mask);
+
+    let rotation = |anim: &spine::skeleton::animation::SkinAnimation, bone: &str| {
+        anim.bone_world_srt(bone, 0.0).unwrap().rotation
+    };
+
+    // lower body keeps its setup pose, upper body is animated
+    let leg = rotation(&upper, "left upper leg");
+    assert_eq!(leg, rotation(&setup, "left upper leg"));
+    assert!(leg != rotation(&walk, "left upper leg"));
+    assert!(rotation(&upper, "head") != rotation(&setup, "head"));
+}
+
+#[test]
+fn layered_animations() {
+    use spine::skeleton::mask::BoneMask;
+
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+    let jump = doc.get_animated_skin("default", Some("jump")).unwrap();
+    let upper = BoneMask::from_root(&doc, "torso").unwrap();
+    let everything = BoneMask::from_root(&doc, "root").unwrap();
+
+    // walk on the whole body, jump on the upper body
+    let pose = walk.sample_layers(0.2, &[(&jump, &upper, 0.5)]).unwrap();
+    let (walk_pose, jump_pose) = (walk.sample(0.2).unwrap(), jump.sample(0.5).unwrap());
+    assert!(walk_pose.get_bones()[8].rotation != jump_pose.get_bones()[8].rotation);
+    assert_eq!(pose.get_bones()[8].rotation, jump_pose.get_bones()[8].rotation);
+    assert_eq!(pose.get_bones()[2].rotation, walk_pose.get_bones()[2].rotation);
+
+    // latter layers take precedence
+    let pose = walk.sample_layers(0.2, &[(&jump, &upper, 0.5), (&walk, &everything, 0.2)]).unwrap();
+    assert_eq!(pose.get_bones()[8].rotation, walk_pose.get_bones()[8].rotation);
+
+    assert!(walk.sample_layers(0.2, &[(&jump, &upper, jump.get_duration() + 1.0)]).is_none());
+}
+
+#[test]
+fn timeline_keys() {
+    use spine::skeleton::KeyCurve;
+
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let info = doc.get_animation_info("step").unwrap();
+
+    let body = info.bone_timeline("body").unwrap().unwrap();
+    let keys: Vec<_> = body.rotate_keys().map(|k| (k.time, *k.value, k.curve)).collect();
+    assert_eq!(keys, vec![(0.0, 0.0, KeyCurve::Linear), (0.5, 10.0, KeyCurve::Linear)]);
+    assert_eq!(body.translate_keys().count(), 0);
+
+    assert!(info.bone_timeline("root").unwrap().is_none());
+    assert!(info.slot_timeline("body").unwrap().is_none());
+    assert!(info.bone_timeline("tail").is_err());
+}
+
+#[test]
+fn retarget_animation() {
+    let src: &[u8] = include_bytes!("events.json");
+    let source = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let src: &[u8] = include_bytes!("example.json");
+    let mut target = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+
+    let mut mapping = std::collections::HashMap::new();
+    mapping.insert("body".to_owned(), "torso".to_owned());
+    target.retarget_animation(&source, "step", &mapping).unwrap();
+    assert!(target.retarget_animation(&source, "run", &mapping).is_err());
+
+    let setup = target.get_animated_skin("default", None).unwrap();
+    let step = target.get_animated_skin("default", Some("step")).unwrap();
+    assert_eq!(step.get_duration(), 0.75);
+
+    // "body" rotation is applied on "torso"
+    let rotation = step.bone_world_srt("torso", 0.5).unwrap().rotation;
+    let expected = setup.bone_world_srt("torso", 0.0).unwrap().rotation + 10f32.to_radians();
+    assert!((rotation - expected).abs() < 1e-5);
+    assert_eq!(step.bone_world_srt("hip", 0.5).unwrap().rotation,
+               setup.bone_world_srt("hip", 0.0).unwrap().rotation);
+}
+
+#[test]
+fn clip_animation() {
+    let src: &[u8] = include_bytes!("example.json");
+    let mut doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    doc.clip_animation("walk", "stride", 0.2, 0.6).unwrap();
+    assert!(doc.clip_animation("crawl", "stride", 0.2, 0.6).is_err());
+
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+    let stride = doc.get_animated_skin("default", Some("stride")).unwrap();
+    assert!((stride.get_duration() - 0.4).abs() < 1e-5);
+
+    // boundaries are sampled from the original animation
+    for &(time, original) in &[(0.0, 0.2), (stride.get_duration(), 0.6)] {
+        let clipped = stride.bones_world_srts(time).unwrap();
+        let original = walk.bones_world_srts(original).unwrap();
+        for (&(_, ref c), &(_, ref o)) in clipped.iter().zip(original.iter()) {
+            assert!((c.rotation - o.rotation).abs() < 1e-3);
+            assert!((c.position[0] - o.position[0]).abs() < 1e-2);
+            assert!((c.position[1] - o.position[1]).abs() < 1e-2);
+        }
+    }
+
+    // events are shifted
+    let src: &[u8] = include_bytes!("events.json");
+    let mut doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    doc.clip_animation("step", "hit", 0.4, 0.6).unwrap();
+    let hit = doc.get_animated_skin("default", Some("hit")).unwrap();
+    let events: Vec<_> = hit.events_between(0.0, hit.get_duration()).map(|e| (e.name, e.time)).collect();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].0, "hit");
+    assert!((events[0].1 - 0.1).abs() < 1e-5);
+}
+
+#[test]
+fn baked_animation() {
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+    let baked = doc.get_animated_skin("default", Some("walk")).unwrap().bake(30.0);
+    assert_eq!(baked.get_duration(), walk.get_duration());
+
+    // frames match the animation
+    for &time in &[0.0, 10.0 / 30.0, walk.get_duration()] {
+        for (b, s) in baked.interpolate(time).unwrap().zip(walk.interpolate(time).unwrap()) {
+            assert_eq!(b.attachment, s.attachment);
+            assert!((b.srt.position[0] - s.srt.position[0]).abs() < 1e-3);
+            assert!((b.srt.position[1] - s.srt.position[1]).abs() < 1e-3);
+            assert!((b.srt.rotation - s.srt.rotation).abs() < 1e-3);
+        }
+    }
+
+    assert_eq!(baked.interpolate(0.5).unwrap().count(), walk.interpolate(0.5).unwrap().count());
+    assert!(baked.interpolate(walk.get_duration() + 1.0).is_none());
+
+    // interpolating in a context gives the same sprites
+    let mut context = spine::skeleton::animation::EvalContext::new();
+    let expected: Vec<_> = baked.interpolate(0.5).unwrap().map(|s| s.positions).collect();
+    let positions: Vec<_> = baked.interpolate_in(0.5, &mut context).unwrap().map(|s| s.positions).collect();
+    assert_eq!(positions, expected);
+
+    for &fps in &[0.0, -30.0, f32::INFINITY] {
+        let result = ::std::panic::catch_unwind(|| doc.get_animated_skin("default", Some("walk")).unwrap().bake(fps));
+        assert!(result.is_err());
+    }
+}
+
+#[test]
+fn exact_curves() {
+    use spine::skeleton::CurveMode;
+
+    let src: &[u8] = include_bytes!("example.json");
+    let approximate = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let mut doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    doc.set_curve_mode(CurveMode::Exact);
+    doc.clip_animation("walk", "stride", 0.2, 0.6).unwrap();
+
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+    let stride = doc.get_animated_skin("default", Some("stride")).unwrap();
+    let approximate = approximate.get_animated_skin("default", Some("walk")).unwrap();
+
+    for &time in &[0.05, 0.1, 0.25, 0.35] {
+        // the approximation stays close to the exact curve
+        for (e, a) in walk.bones_world_srts(time).unwrap().iter()
+                          .zip(approximate.bones_world_srts(time).unwrap().iter()) {
+            assert!((e.1.rotation - a.1.rotation).abs() < 1e-2);
+        }
+
+        // curves cut by a clip are exact
+        for (c, o) in stride.bones_world_srts(time).unwrap().iter()
+                            .zip(walk.bones_world_srts(time + 0.2).unwrap().iter()) {
+            assert!((c.1.rotation - o.1.rotation).abs() < 1e-3);
+            assert!((c.1.position[1] - o.1.position[1]).abs() < 1e-2);
+        }
+    }
+}
+
+#[test]
+fn flip() {
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let mut anim = doc.get_animated_skin("default", Some("step")).unwrap();
+
+    anim.set_flip(true, false);
+    assert_eq!(anim.get_flip(), (true, false));
+    let sprite = anim.interpolate(0.0).unwrap().next().unwrap();
+    assert_eq!(sprite.positions, [[10.0, 30.0], [-10.0, 30.0], [-10.0, -10.0], [10.0, -10.0]]);
+
+    // rotations are mirrored too
+    let flipped = anim.bone_world_srt("body", 0.5).unwrap();
+    anim.set_flip(false, true);
+    let original = doc.get_animated_skin("default", Some("step")).unwrap().bone_world_srt("body", 0.5).unwrap();
+    assert!((flipped.rotation + original.rotation).abs() < 1e-6);
+    let mirrored = anim.bone_world_srt("body", 0.5).unwrap();
+    assert_eq!(mirrored.position, [0.0, -10.0]);
+    assert!((mirrored.rotation + original.rotation).abs() < 1e-6);
+
+    let mut instance = spine::skeleton::animation::SkeletonInstance::new(std::sync::Arc::new(doc), "default").unwrap();
+    instance.set_flip(true, true);
+    instance.set_animation(Some("step")).unwrap();
+    assert_eq!(instance.get_flip(), (true, true));
+    assert_eq!(instance.bone_world_srt("body").unwrap().position, [0.0, -10.0]);
+}
+
+#[test]
+fn skeleton_from_slice() {
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_slice(src).unwrap();
+    assert_eq!(doc.get_bones_names(), spine::skeleton::Skeleton::from_reader(src).unwrap().get_bones_names());
+
+    // escaped names cannot be borrowed
+    let src: &[u8] = br#"{ "bones": [ { "name": "root" }, { "name": "left \"arm\"", "parent": "root" } ],
+                           "slots": [ { "name": "arm", "bone": "left \"arm\"" } ] }"#;
+    let doc = spine::skeleton::Skeleton::from_slice(src).unwrap();
+    assert_eq!(doc.get_bones_names(), ["root", "left \"arm\""]);
+}
+
+#[test]
+fn skeleton_from_file_and_str() {
+    use spine::skeleton::Skeleton;
+    use spine::skeleton::error::SkeletonError;
+
+    let doc = Skeleton::from_file("tests/events.json").unwrap();
+    assert_eq!(doc.get_bones_names(), ["root", "body"]);
+    match Skeleton::from_file("tests/missing.json") {
+        Err(SkeletonError::IoError(_)) => (),
+        _ => panic!("the file must be missing")
+    }
+
+    let doc: Skeleton = include_str!("events.json").parse().unwrap();
+    assert_eq!(doc.get_slots_names(), ["body"]);
+}
+
+#[test]
+fn skeleton_from_json_value() {
+    use spine::skeleton::Skeleton;
+    use spine::skeleton::error::SkeletonError;
+
+    let mut value: serde_json::Value = serde_json::from_str(include_str!("events.json")).unwrap();
+    value["slots"][0]["color"] = serde_json::Value::from("00ff00ff");
+    let doc = Skeleton::from_json_value(&value).unwrap();
+    let anim = doc.get_animated_skin("default", None).unwrap();
+    assert_eq!(anim.interpolate(0.0).unwrap().next().unwrap().color, [0, 255, 0, 255]);
+
+    value["slots"][0]["color"] = serde_json::Value::from(3);
+    match Skeleton::from_json_value(&value) {
+        Err(SkeletonError::JsonError(ref path, _)) => assert_eq!(path, "slots[0].color"),
+        _ => panic!("the color must be invalid")
+    }
+}
+
+#[test]
+fn json_error_path() {
+    use spine::skeleton::error::SkeletonError;
+
+    let src: &[u8] = br#"{ "bones": [ { "name": "root" }, { "name": "left arm", "parent": "root" } ],
+                           "animations": { "walk": { "bones": { "left arm": { "rotate": [
+                               { "time": 0, "angle": 0 },
+                               { "time": 1, "angle": 10, "curve": "bouncy" }
+                           ] } } },
+                                          "idle": { "bones": { "left arm": { "rotate": [
+                               { "time": 0, "angle": 0 }
+                           ] } } } } }"#;
+
+    // animations are parsed when first requested
+    let doc = spine::skeleton::Skeleton::from_slice(src).unwrap();
+    assert_eq!(doc.get_animation_info("idle").unwrap().get_duration(), 0.0);
+    match doc.get_animation_info("walk") {
+        Err(SkeletonError::JsonError(ref path, ref e)) => {
+            assert_eq!(path, "animations.walk.bones.\"left arm\".rotate[1].curve");
+            assert!(e.to_string().contains("bouncy"));
+        },
+        _ => panic!("the curve must be invalid")
+    }
+}
+
+#[test]
+fn skeleton_loader() {
+    use spine::skeleton::loader::SkeletonLoader;
+
+    let src: &[u8] = include_bytes!("hitbox.json");
+    let doc = SkeletonLoader::new().animations(&["hurt"]).skins(&["goblin"]).from_slice(src).unwrap();
+    assert_eq!(doc.get_animations_names(), ["hurt"]);
+    assert_eq!(doc.get_skins_names(), ["default", "goblin"]);
+    assert!(doc.get_animated_skin("goblin", Some("hurt")).is_ok());
+    assert!(doc.get_animated_skin("goblin", Some("move")).is_err());
+
+    let doc = SkeletonLoader::new().skins(&[]).from_reader(src).unwrap();
+    assert_eq!(doc.get_skins_names(), ["default"]);
+    assert_eq!(doc.get_animations_names().len(), 2);
+    assert!(SkeletonLoader::new().animations(&["crawl"]).from_slice(src).is_err());
+}
+
+#[test]
+fn preload_animations() {
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    doc.preload_animations().unwrap();
+    assert_eq!(doc.get_animation_info("jump").unwrap().get_duration(),
+               spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap()
+                   .get_animation_info("jump").unwrap().get_duration());
+
+    let src: &[u8] = br#"{ "bones": [ { "name": "root" } ], "animations": {
+                           "idle": {}, "walk": { "bones": { "tail": {} } } } }"#;
+    let doc = spine::skeleton::Skeleton::from_slice(src).unwrap();
+    assert!(doc.preload_animations().is_err());
+}
+
+#[test]
+fn merge_animations() {
+    let src: &[u8] = include_bytes!("example.json");
+    let mut doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+
+    let wave: &[u8] = br#"{ "animations": { "wave": { "bones": { "left arm": { "rotate": [
+                               { "time": 0, "angle": 0 }, { "time": 0.5, "angle": 40 }
+                           ] } } } } }"#;
+    doc.merge_animations_from_reader(wave).unwrap();
+    assert_eq!(doc.get_animations_names(), ["walk", "jump", "wave"]);
+    assert_eq!(doc.get_animation_info("wave").unwrap().get_duration(), 0.5);
+
+    let tail: &[u8] = br#"{ "animations": { "swing": {}, "wag": { "bones": { "tail": {} } } } }"#;
+    assert!(doc.merge_animations_from_reader(tail).is_err());
+    assert!(doc.get_animated_skin("default", Some("swing")).is_err());
+}
+
+#[test]
+fn scaled_skeleton() {
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let half = spine::skeleton::Skeleton::from_reader_scaled(BufReader::new(src), 0.5).unwrap();
+
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+    let half_walk = half.get_animated_skin("default", Some("walk")).unwrap();
+    for (s, h) in walk.interpolate(0.3).unwrap().zip(half_walk.interpolate(0.3).unwrap()) {
+        assert!((s.srt.rotation - h.srt.rotation).abs() < 1e-5);
+        for (p, q) in s.positions.iter().zip(h.positions.iter()) {
+            assert!((p[0] / 2.0 - q[0]).abs() < 1e-3);
+            assert!((p[1] / 2.0 - q[1]).abs() < 1e-3);
+        }
+    }
+}
+
+#[test]
+fn y_down() {
+    use spine::skeleton::CoordinateSystem;
+
+    let src: &[u8] = include_bytes!("events.json");
+    let mut doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    doc.set_coordinate_system(CoordinateSystem::YDown);
+    assert_eq!(doc.get_coordinate_system(), CoordinateSystem::YDown);
+
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+    let sprite = anim.interpolate(0.0).unwrap().next().unwrap();
+    assert_eq!(sprite.positions, [[-10.0, -30.0], [10.0, -30.0], [10.0, 10.0], [-10.0, 10.0]]);
+    assert_eq!(anim.bounds(0.0), Some([[-10.0, -30.0], [10.0, 10.0]]));
+    assert!(anim.bone_world_srt("body", 0.5).unwrap().rotation < 0.0);
+}
+
+#[test]
+fn shortest_rotation() {
+    let src: &[u8] = include_bytes!("events.json");
+    let mut doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+
+    // 170 to 190 (-170) degrees turns by 20 degrees through 180
+    let rotation = |doc: &spine::skeleton::Skeleton| {
+        let spin = doc.get_animated_skin("default", Some("spin")).unwrap();
+        spin.bones_world_srts(0.25).unwrap()[1].1.rotation.to_degrees()
+    };
+    assert!((rotation(&doc) - 180.0).abs() < 1e-3);
+
+    doc.set_shortest_rotation(false);
+    assert!(rotation(&doc).abs() < 1e-3);
+}
+
+#[test]
+fn setup_pose() {
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let setup = doc.get_animated_skin("default", None).unwrap();
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+
+    let expected: Vec<_> = setup.interpolate(0.0).unwrap().map(|s| (s.attachment, s.positions)).collect();
+    let sprites: Vec<_> = walk.setup_pose().map(|s| (s.attachment, s.positions)).collect();
+    assert_eq!(sprites, expected);
+
+    let animated: Vec<_> = walk.interpolate(0.1).unwrap().map(|s| s.positions).collect();
+    assert!(sprites.iter().zip(animated.iter()).any(|(s, a)| s.1 != *a));
+}
+
+#[test]
+fn sprite_slot() {
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let anim = doc.get_animated_skin("default", None).unwrap();
+
+    let eyes = anim.interpolate(0.0).unwrap().find(|s| s.slot == "eyes").unwrap();
+    assert_eq!(eyes.bone, "head");
+    assert_eq!(eyes.draw_index, 13);
+
+    for (i, sprite) in anim.interpolate(0.0).unwrap().slots().enumerate() {
+        assert_eq!(sprite.map(|s| s.draw_index).unwrap_or(i), i);
+    }
+}
+
+#[test]
+fn timed_animation_iter() {
+    use spine::skeleton::animation::AnimationEnd;
+
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+
+    let times: Vec<_> = anim.run(0.25).timed().map(|(time, _)| time).collect();
+    assert_eq!(times, vec![0.0, 0.25, 0.5, 0.75]);
+
+    let mut iter = anim.run(0.25).end(AnimationEnd::Loop);
+    assert_eq!(iter.get_remaining(), 0.75);
+    iter.nth(3);
+    assert_eq!(iter.get_time(), Some(0.25));
+    assert_eq!(iter.get_remaining(), 0.5);
+
+    let mut iter = anim.run(0.25);
+    iter.nth(3);
+    assert_eq!(iter.get_time(), None);
+    assert_eq!(iter.get_remaining(), 0.0);
+}
+
+#[test]
+fn controller() {
+    use spine::skeleton::animation::AnimationEnd;
+    use spine::skeleton::controller::SpineController;
+
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let mut controller = SpineController::new(doc.get_animated_skin("default", Some("step")).unwrap());
+
+    controller.update(0.25);
+    controller.pause();
+    controller.update(0.25);
+    assert_eq!(controller.get_time(), 0.25);
+    controller.resume();
+    controller.set_speed(2.0);
+    controller.update(0.125);
+    assert_eq!(controller.get_time(), 0.5);
+    assert!(!controller.is_finished());
+
+    controller.update(0.25);
+    assert!(controller.is_finished());
+    assert!(controller.interpolate().is_none());
+
+    controller.set_end(AnimationEnd::Loop);
+    controller.seek(0.5);
+    controller.update(0.25);
+    assert_eq!(controller.get_time(), 0.25);
+    assert!(!controller.is_finished());
+
+    controller.set_end(AnimationEnd::Clamp);
+    controller.update(1.0);
+    assert_eq!(controller.get_time(), 0.75);
+    assert!(controller.is_finished());
+    assert!(controller.interpolate().is_some());
+}
+
+#[test]
+fn controller_progress() {
+    use spine::skeleton::animation::AnimationEnd;
+    use spine::skeleton::controller::SpineController;
+
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let mut controller = SpineController::new(doc.get_animated_skin("default", Some("step")).unwrap());
+
+    controller.set_end(AnimationEnd::Clamp);
+    assert!(!controller.update(0.5).completed);
+    assert!(controller.update(0.5).completed);
+    assert!(!controller.update(0.5).completed);
+
+    controller.set_end(AnimationEnd::Loop);
+    controller.seek(0.5);
+    assert_eq!(controller.update(0.25).loops, 1);
+    assert_eq!(controller.update(1.75).loops, 2);
+    assert_eq!(controller.update(0.25).loops, 0);
+
+    controller.set_speed(-1.0);
+    controller.seek(0.25);
+    assert_eq!(controller.update(0.5).loops, 1);
+    assert!(!controller.update(0.5).completed);
+}
+
+#[test]
+fn controller_listener() {
+    use spine::skeleton::animation::AnimationEnd;
+    use spine::skeleton::controller::{AnimationListener, SpineController};
+    use spine::skeleton::events::Event;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    struct Listener(Rc<RefCell<Vec<String>>>);
+    impl AnimationListener for Listener {
+        fn on_start(&mut self) { self.0.borrow_mut().push("start".to_owned()); }
+        fn on_complete(&mut self) { self.0.borrow_mut().push("complete".to_owned()); }
+        fn on_loop(&mut self) { self.0.borrow_mut().push("loop".to_owned()); }
+        fn on_event(&mut self, event: Event) { self.0.borrow_mut().push(event.name.to_owned()); }
+    }
+
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let calls = Rc::new(RefCell::new(Vec::new()));
+
+    let mut controller = SpineController::new(doc.get_animated_skin("default", Some("step")).unwrap());
+    controller.set_listener(Some(Box::new(Listener(calls.clone()))));
+    controller.set_end(AnimationEnd::Loop);
+    controller.update(0.5);
+    controller.update(0.5);
+    assert_eq!(*calls.borrow(), vec!["start", "footstep", "hit", "footstep", "loop", "footstep"]);
+
+    calls.borrow_mut().clear();
+    controller.set_end(AnimationEnd::Stop);
+    controller.update(1.0);
+    controller.update(1.0);
+    assert_eq!(*calls.borrow(), vec!["hit", "footstep", "complete"]);
+}
+
+#[test]
+fn fixed_step_controller() {
+    use spine::skeleton::controller::{FixedStepController, SpineController};
+
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+    let mut fixed = FixedStepController::new(SpineController::new(anim), 0.1);
+
+    fixed.advance(0.25);
+    assert!((fixed.get_controller().get_time() - 0.2).abs() < 1e-5);
+    assert!((fixed.get_alpha() - 0.5).abs() < 1e-4);
+
+    // halfway between ticks at 0.1 and 0.2
+    let pose = fixed.sample(fixed.get_alpha());
+    let expected = fixed.get_controller().get_animation().sample(0.15).unwrap();
+    assert!((pose.get_bones()[1].rotation - expected.get_bones()[1].rotation).abs() < 1e-4);
+
+    assert!(fixed.advance(1.0).completed);
+    fixed.sample(1.0);
+
+    // a step which cannot consume elapsed time is rejected
+    for &step in &[0.0, -0.1, f32::NAN] {
+        let result = ::std::panic::catch_unwind(|| {
+            let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+            FixedStepController::new(SpineController::new(anim), step);
+        });
+        assert!(result.is_err());
+    }
+}
+
+#[test]
+fn loop_crossfade() {
+    use spine::skeleton::animation::AnimationEnd;
+
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+    let rotation = |sprites: Option<spine::skeleton::animation::Sprites>| {
+        sprites.unwrap().next().unwrap().srt.rotation
+    };
+
+    // the last frame matches the first one, the window start is untouched
+    let first = rotation(anim.interpolate(0.0));
+    assert!((rotation(anim.interpolate_looped(0.75, 0.25)) - first).abs() < 1e-5);
+    assert_eq!(rotation(anim.interpolate_looped(0.5, 0.25)), rotation(anim.interpolate(0.5)));
+    assert!(rotation(anim.interpolate_looped(0.75, 0.25)) != rotation(anim.interpolate(0.75)));
+
+    let mut looped = anim.run(0.25).end(AnimationEnd::Loop).crossfade(0.25).skip(3);
+    assert!((rotation(looped.next()) - first).abs() < 1e-5);
+}
+
+#[test]
+fn empty_animation() {
+    use spine::skeleton::state::AnimationState;
+
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let mut state = AnimationState::new(&doc, "default").unwrap();
+    let rotation = |state: &AnimationState| state.sample().get_bones()[1].rotation.to_degrees();
+
+    state.set_animation(1, "step", false, 0.0).unwrap();
+    assert!(state.set_animation(1, "run", false, 0.0).is_err());
+    state.update(0.5);
+    assert!((rotation(&state) - 10.0).abs() < 1e-4);
+
+    // fades back to the setup pose, then clears the track
+    state.set_empty_animation(1, 0.5);
+    assert_eq!(state.get_animation(1), None);
+    state.update(0.25);
+    assert!((rotation(&state) - 5.0).abs() < 1e-4);
+    state.update(0.25);
+    assert!(rotation(&state).abs() < 1e-4);
+    assert_eq!(state.get_time(1), None);
+
+    let sprites = state.get_setup().pose_sprites(&state.sample()).count();
+    assert_eq!(sprites, 1);
+}
+
+#[test]
+fn track_alpha() {
+    use spine::skeleton::state::AnimationState;
+
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let mut state = AnimationState::new(&doc, "default").unwrap();
+    let rotation = |state: &AnimationState| state.sample().get_bones()[1].rotation.to_degrees();
+
+    state.set_animation(1, "step", false, 0.0).unwrap();
+    state.update(0.5);
+    assert_eq!(state.get_track_alpha(1), Some(1.0));
+    assert_eq!(state.get_track_alpha(2), None);
+    state.set_track_alpha(1, 0.3);
+    assert!((rotation(&state) - 3.0).abs() < 1e-4);
+
+    state.set_animation(1, "step", false, 0.0).unwrap();
+    assert_eq!(state.get_track_alpha(1), Some(1.0));
+}
+
+#[test]
+fn hold_previous() {
+    use spine::skeleton::state::AnimationState;
+
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let rotation = |state: &AnimationState| state.sample().get_bones()[1].rotation.to_degrees();
+
+    // "fade" has no body timeline, the body dips towards the setup pose unless held
+    for &(hold, expected) in &[(false, 5.0), (true, 10.0)] {
+        let mut state = AnimationState::new(&doc, "default").unwrap();
+        state.set_animation(0, "step", false, 0.0).unwrap();
+        state.update(0.5);
+        state.set_animation(0, "fade", false, 0.5).unwrap();
+        state.set_hold_previous(0, hold);
+        state.update(0.25);
+        assert!((rotation(&state) - expected).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn sync_markers() {
+    use spine::skeleton::sync::SyncMarkers;
+
+    let walk = SyncMarkers::new(1.0, vec![0.5, 0.0]);
+    let run = SyncMarkers::new(2.0, vec![0.0, 0.5]);
+    assert_eq!(walk.get_phase(0.25), 0.25);
+    assert_eq!(run.get_time(walk.get_phase(0.25)), 0.25);
+    assert_eq!(run.get_time(walk.get_phase(0.75)), 1.25);
+    assert!((run.get_phase(run.get_time(0.9)) - 0.9).abs() < 1e-5);
+
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let step = doc.get_animated_skin("default", Some("step")).unwrap();
+    let markers = SyncMarkers::from_events(&step, "footstep");
+    assert_eq!(markers.get_markers(), &[0.25, 0.75]);
+    assert_eq!(markers.get_phase(0.5), 0.25);
+    assert!((markers.get_phase(0.1) - 0.7).abs() < 1e-5);
+    assert!((markers.get_time(0.7) - 0.1).abs() < 1e-5);
+}
+
+#[test]
+fn mirror_pose() {
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+    let center = |p: [[f32; 2]; 4]| [(p[0][0] + p[2][0]) / 2.0, (p[0][1] + p[2][1]) / 2.0];
+
+    // every sprite is mirrored across the y axis
+    let mut mapping = std::collections::HashMap::new();
+    let pose = walk.sample(0.3).unwrap();
+    let mirrored = walk.mirror_pose(&pose, &mapping).unwrap();
+    let sprites = walk.pose_sprites(&pose).zip(walk.pose_sprites(&mirrored));
+    for (sprite, mirrored) in sprites {
+        assert_eq!(sprite.attachment, mirrored.attachment);
+        let (expected, center) = (center(sprite.positions), center(mirrored.positions));
+        assert!((center[0] + expected[0]).abs() < 1e-3, "{:?} != {:?}", center, expected);
+        assert!((center[1] - expected[1]).abs() < 1e-3, "{:?} != {:?}", center, expected);
+    }
+
+    // mapped bones take the transform of each other
+    mapping.insert("left upper leg".to_owned(), "right upper leg".to_owned());
+    let swapped = walk.mirror_pose(&pose, &mapping).unwrap();
+    let names = doc.get_bones_names();
+    let index = |name: &str| names.iter().position(|&b| b == name).unwrap();
+    let (left, right) = (index("left upper leg"), index("right upper leg"));
+    assert_eq!(swapped.get_bones()[left].rotation, mirrored.get_bones()[right].rotation);
+    assert_eq!(swapped.get_bones()[right].position, mirrored.get_bones()[left].position);
+
+    mapping.insert("tail".to_owned(), "head".to_owned());
+    assert!(walk.mirror_pose(&pose, &mapping).is_err());
+}
+
+#[test]
+fn two_bone_ik() {
+    use spine::skeleton::ik::solve_two_bone;
+
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let setup = doc.get_animated_skin("default", None).unwrap();
+
+    for &bend_positive in &[true, false] {
+        let mut pose = setup.sample(0.0).unwrap();
+        solve_two_bone(&doc, &mut pose, "left upper leg", "left lower leg", [30.0, 30.0], bend_positive).unwrap();
+        let leg = setup.pose_sprites(&pose).find(|s| s.slot == "left lower leg").unwrap();
+        let tip = leg.srt.transform([56.45, 0.0]);
+        assert!((tip[0] - 30.0).abs() < 1e-2 && (tip[1] - 30.0).abs() < 1e-2);
+    }
+
+    let mut pose = setup.sample(0.0).unwrap();
+    assert!(solve_two_bone(&doc, &mut pose, "hip", "left lower leg", [0.0, 0.0], true).is_err());
+}
+
+#[test]
+fn aim_at() {
+    use spine::skeleton::ik::aim_at;
+
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let setup = doc.get_animated_skin("default", None).unwrap();
+    let head = |pose: &spine::skeleton::animation::SampledPose| {
+        setup.pose_sprites(pose).find(|s| s.slot == "head").unwrap().srt
+    };
+
+    let mut pose = setup.sample(0.0).unwrap();
+    let position = head(&pose).position;
+    let target = [position[0] + 100.0, position[1] + 100.0];
+    aim_at(&doc, &mut pose, "head", target, None, 1.0).unwrap();
+    assert!((head(&pose).rotation.to_degrees() - 45.0).abs() < 1e-3);
+
+    // half weight from the setup rotation, then clamped
+    let setup_rotation = head(&setup.sample(0.0).unwrap()).rotation.to_degrees();
+    let mut pose = setup.sample(0.0).unwrap();
+    aim_at(&doc, &mut pose, "head", target, None, 0.5).unwrap();
+    assert!((head(&pose).rotation.to_degrees() - (setup_rotation + 45.0) / 2.0).abs() < 1e-3);
+
+    let mut pose = setup.sample(0.0).unwrap();
+    aim_at(&doc, &mut pose, "head", target, Some([-10.0, 10.0]), 1.0).unwrap();
+    let index = setup.bones_world_srts(0.0).unwrap().iter().position(|&(b, _)| b == "head").unwrap();
+    assert!((pose.get_bones()[index].rotation.to_degrees() + 10.0).abs() < 1e-3);
+    assert!(aim_at(&doc, &mut pose, "tail", target, None, 1.0).is_err());
+}
+
+#[test]
+fn pose_limits() {
+    use spine::skeleton::pose::{Pose, PoseMode};
+
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+
+    // the animation rotates the body up to 10 degrees, aiming adds 30
+    let mut pose = Pose::new(&doc);
+    pose.set_bone_rotation("body", 30.0, PoseMode::Relative).unwrap();
+    pose.set_bone_rotation_limits("body", -20.0, 20.0).unwrap();
+    pose.set_bone_translation_limits("body", [-5.0, -5.0], [5.0, 5.0]).unwrap();
+    assert!(pose.set_bone_rotation_limits("tail", -20.0, 20.0).is_err());
+
+    let sprite = anim.interpolate_with_pose(0.0, &pose).unwrap().next().unwrap();
+    assert!((sprite.srt.rotation.to_degrees() - 20.0).abs() < 1e-4);
+    assert_eq!(sprite.srt.position, [0.0, 5.0]);
+}
+
+#[test]
+fn spring_bones() {
+    use spine::skeleton::spring::SpringBones;
+
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let setup = doc.get_animated_skin("default", None).unwrap();
+    let mut springs = SpringBones::new();
+    springs.add_bone(&doc, "body", 200.0, 20.0, 0.0).unwrap();
+    assert!(springs.add_bone(&doc, "tail", 200.0, 20.0, 0.0).is_err());
+
+    let mut pose = setup.sample(0.0).unwrap();
+    springs.update(&doc, &mut pose, 1.0 / 60.0);
+    assert!(pose.get_bones()[1].rotation.abs() < 1e-5);
+
+    // the root moves up, the body lags behind then settles back
+    let mut rotations = Vec::new();
+    for _ in 0..300 {
+        let mut pose = setup.sample(0.0).unwrap();
+        pose.get_bones_mut()[0].position[1] = 10.0;
+        springs.update(&doc, &mut pose, 1.0 / 60.0);
+        rotations.push(pose.get_bones()[1].rotation);
+    }
+    assert!(rotations[0].abs() > 0.1);
+    assert!(rotations[299].abs() < 1e-3);
+}
+
+#[test]
+fn mounted_instances() {
+    use spine::skeleton::animation::SkeletonInstance;
+    use std::sync::Arc;
+
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = Arc::new(spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap());
+    let mut horse = SkeletonInstance::new(doc.clone(), "default").unwrap();
+    horse.set_animation(Some("walk")).unwrap();
+    horse.advance(0.3);
+    let mut rider = SkeletonInstance::new(doc.clone(), "default").unwrap();
+    assert!(rider.mount(&horse, "saddle").is_err());
+    rider.mount(&horse, "torso").unwrap();
+
+    // the root of the rider follows the bone of the horse
+    let torso = horse.bone_world_srt("torso").unwrap();
+    let root = rider.bone_world_srt("root").unwrap();
+    assert!((root.position[0] - torso.position[0]).abs() < 1e-4);
+    assert!((root.position[1] - torso.position[1]).abs() < 1e-4);
+    assert!((root.rotation - torso.rotation).abs() < 1e-5);
+
+    // the rider is drawn right after the torso of the horse
+    let horse_order: Vec<_> = horse.interpolate().unwrap().map(|s| s.draw_index).collect();
+    let rider_order: Vec<_> = rider.interpolate().unwrap().map(|s| s.draw_index).collect();
+    let order: Vec<_> = horse.interpolate_mounted(&rider, "torso").unwrap().unwrap()
+        .map(|s| s.draw_index).collect();
+    let split = horse_order.iter().position(|&i| i > 10).unwrap();
+    let expected: Vec<_> = horse_order[..split].iter().chain(rider_order.iter())
+        .chain(horse_order[split..].iter()).cloned().collect();
+    assert_eq!(order, expected);
+    assert!(horse.interpolate_mounted(&rider, "tail").is_err());
+}
+
+#[test]
+fn frozen_pose() {
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+    let frozen = doc.get_animated_skin("default", Some("walk")).unwrap().freeze(0.3);
+
+    assert_eq!(frozen.get_time(), 0.3);
+    for (a, b) in frozen.sprites().zip(walk.interpolate(0.3).unwrap()) {
+        assert_eq!(a.attachment, b.attachment);
+        assert_eq!(a.srt.position, b.srt.position);
+        assert_eq!(a.color, b.color);
+    }
+    assert_eq!(frozen.sprites().count(), walk.interpolate(0.3).unwrap().count());
+
+    let last = doc.get_animated_skin("default", Some("walk")).unwrap().freeze(100.0);
+    assert_eq!(last.get_time(), walk.get_duration());
+}
+
+#[test]
+fn slot_color_tints() {
+    use spine::skeleton::animation::SkeletonInstance;
+    use std::sync::Arc;
+
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = Arc::new(spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap());
+    let mut instance = SkeletonInstance::new(doc, "default").unwrap();
+
+    instance.set_slot_color("torso", [1.0, 0.0, 0.5, 1.0]).unwrap();
+    assert!(instance.set_slot_color("tail", [1.0, 0.0, 0.0, 1.0]).is_err());
+    for sprite in instance.interpolate().unwrap() {
+        if sprite.slot == "torso" {
+            assert_eq!(sprite.color, [255, 0, 128, 255]);
+        } else {
+            assert_eq!(sprite.color, [255, 255, 255, 255]);
+        }
+    }
+
+    instance.clear_slot_color("torso").unwrap();
+    assert!(instance.interpolate().unwrap().all(|s| s.color == [255, 255, 255, 255]));
+}
+
+#[test]
+fn opacity() {
+    use spine::skeleton::animation::SkeletonInstance;
+    use std::sync::Arc;
+
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = Arc::new(spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap());
+    let mut walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+    assert_eq!(walk.get_opacity(), 1.0);
+    walk.set_opacity(0.5);
+    assert!(walk.interpolate(0.3).unwrap().all(|s| s.color[3] == 128 && s.color_f32[3] == 0.5));
+
+    let mut instance = SkeletonInstance::new(doc.clone(), "default").unwrap();
+    instance.set_opacity(0.0);
+    instance.set_animation(Some("walk")).unwrap();
+    assert!(instance.interpolate().unwrap().all(|s| s.color[3] == 0));
+}
+
+#[test]
+fn skip_transparent() {
+    use spine::skeleton::animation::SkeletonInstance;
+    use std::sync::Arc;
+
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = Arc::new(spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap());
+    let mut instance = SkeletonInstance::new(doc, "default").unwrap();
+    let count = instance.interpolate().unwrap().count();
+    instance.set_slot_color("torso", [1.0, 1.0, 1.0, 0.0]).unwrap();
+
+    // transparent sprites are still drawn unless skipped
+    assert_eq!(instance.interpolate().unwrap().count(), count);
+    let sprites = instance.interpolate().unwrap().skip_transparent();
+    assert_eq!(sprites.len(), count - 1);
+    assert!(sprites.into_iter().all(|s| s.slot != "torso"));
+    let slots: Vec<_> = instance.interpolate().unwrap().skip_transparent().slots().collect();
+    assert!(slots[10].is_none());
+}
+
+#[test]
+fn filter_slots() {
+    use spine::skeleton::mask::BoneMask;
+
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+    let mask = BoneMask::from_slots(&doc, &["torso", "head"]).unwrap();
+    assert!(BoneMask::from_slots(&doc, &["shadow"]).is_err());
+
+    let sprites = walk.interpolate(0.3).unwrap().filter_slots(&mask);
+    assert_eq!(sprites.len(), 2);
+    let names: Vec<_> = sprites.map(|s| s.slot).collect();
+    assert_eq!(names, ["torso", "head"]);
+    let slots = walk.interpolate(0.3).unwrap().filter_slots(&mask).slots();
+    assert_eq!(slots.filter(|s| s.is_some()).count(), 2);
+}
+
+#[test]
+fn save_playback_state() {
+    use spine::skeleton::animation::{InstanceState, SkeletonInstance};
+    use spine::skeleton::state::{AnimationState, TrackState};
+    use std::sync::Arc;
+
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = Arc::new(spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap());
+    let mut instance = SkeletonInstance::new(doc.clone(), "default").unwrap();
+    instance.set_animation(Some("walk")).unwrap();
+    instance.advance(0.3);
+    instance.set_attachment("eyes", Some("eyes-closed")).unwrap();
+    instance.set_slot_color("torso", [1.0, 0.0, 0.0, 1.0]).unwrap();
+
+    let saved = serde_json::to_string(&instance.save_state()).unwrap();
+    let state: InstanceState = serde_json::from_str(&saved).unwrap();
+    assert_eq!(state, instance.save_state());
+
+    let mut restored = SkeletonInstance::new(doc.clone(), "default").unwrap();
+    restored.restore_state(&state).unwrap();
+    for (a, b) in restored.interpolate().unwrap().zip(instance.interpolate().unwrap()) {
+        assert_eq!(a.attachment, b.attachment);
+        assert_eq!(a.srt.position, b.srt.position);
+        assert_eq!(a.color, b.color);
+    }
+    let mut missing = state.clone();
+    missing.animation = Some("crawl".to_owned());
+    assert!(restored.restore_state(&missing).is_err());
+    assert_eq!(restored.get_animation(), Some("walk"));
+
+    // tracks are restored in the middle of their mix
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let mut tracks = AnimationState::new(&doc, "default").unwrap();
+    tracks.set_animation(0, "step", true, 0.0).unwrap();
+    tracks.update(0.5);
+    tracks.set_animation(0, "spin", false, 0.5).unwrap();
+    tracks.update(0.25);
+
+    let saved = serde_json::to_string(&tracks.save_state()).unwrap();
+    let state: Vec<Option<TrackState>> = serde_json::from_str(&saved).unwrap();
+    let mut restored = AnimationState::new(&doc, "default").unwrap();
+    restored.restore_state(&state).unwrap();
+    assert_eq!(restored.get_animation(0), Some("spin"));
+    assert_eq!(restored.sample().get_bones()[1].rotation, tracks.sample().get_bones()[1].rotation);
+}
+
+#[test]
+fn bone_matrices() {
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+
+    let names = doc.get_bones_names();
+    let matrices = walk.bone_matrices(0.3).unwrap();
+    let matrices4 = walk.bone_matrices4(0.3).unwrap();
+    assert_eq!(matrices.len(), names.len());
+    for (i, (name, srt)) in walk.bones_world_srts(0.3).unwrap().into_iter().enumerate() {
+        let (m, m4) = (&matrices[i], &matrices4[i]);
+        assert_eq!(names[i], name);
+        let p = srt.transform([3.0, 4.0]);
+        let q = [m[0][0] * 3.0 + m[1][0] * 4.0 + m[2][0], m[0][1] * 3.0 + m[1][1] * 4.0 + m[2][1]];
+        assert!((p[0] - q[0]).abs() < 1e-3 && (p[1] - q[1]).abs() < 1e-3);
+        assert_eq!([m4[3][0], m4[3][1]], srt.position);
+    }
+    assert!(walk.bone_matrices(walk.get_duration() + 1.0).is_none());
+}
+
+#[test]
+fn bones_world_srts_reference() {
+    use spine::skeleton::SRT;
+
+    let src: &[u8] = include_bytes!("example.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(src).unwrap();
+    let bones = json["bones"].as_array().unwrap();
+    let names = doc.get_bones_names();
+    let parents: Vec<Option<usize>> = bones.iter()
+        .map(|b| b["parent"].as_str().map(|p| names.iter().position(|&n| n == p).unwrap())).collect();
+    let inherits = |i: usize, key: &str| bones[i][key].as_bool().unwrap_or(true);
+
+    let mut walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+    for &flip in &[false, true] {
+        walk.set_flip(flip, false);
+        for &time in &[0.0, 0.3, 0.65, walk.get_duration()] {
+
+            // each bone inherits from the world srt of its parent, one bone at a time
+            let mut expected: Vec<SRT> = Vec::new();
+            for (i, local) in walk.sample(time).unwrap().get_bones().iter().enumerate() {
+                let mut srt = local.clone();
+                if let Some(p) = parents[i] {
+                    let parent = expected[p].clone();
+                    srt.position = parent.transform(srt.position);
+                    if inherits(i, "inheritRotation") {
+                        srt.rotation += parent.rotation;
+                    }
+                    if inherits(i, "inheritScale") {
+                        srt.scale = [srt.scale[0] * parent.scale[0], srt.scale[1] * parent.scale[1]];
+                    }
+                }
+                srt.cos = srt.rotation.cos();
+                srt.sin = srt.rotation.sin();
+                expected.push(srt);
+            }
+
+            let srts = walk.bones_world_srts(time).unwrap();
+            assert_eq!(srts.len(), expected.len());
+            for ((name, srt), (mut expected, &bone)) in srts.into_iter().zip(expected.into_iter().zip(names.iter())) {
+                assert_eq!(name, bone);
+                if flip {
+                    expected.position[0] = -expected.position[0];
+                    expected.scale[0] = -expected.scale[0];
+                    expected.rotation = -expected.rotation;
+                    expected.sin = -expected.sin;
+                }
+                let values = [srt.position[0], srt.position[1], srt.scale[0], srt.scale[1], srt.rotation, srt.cos, srt.sin];
+                let reference = [expected.position[0], expected.position[1], expected.scale[0], expected.scale[1],
+                                 expected.rotation, expected.cos, expected.sin];
+                for (a, b) in values.iter().zip(reference.iter()) {
+                    assert!((a - b).abs() < 1e-3, "{} at {} (flip {}): {:?} != {:?}", name, time, flip, srt, expected);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn write_bone_rows() {
+    use spine::skeleton::animation::EvalContext;
+
+    let src: &[u8] = include_bytes!("example.json");
+    let mut doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    doc.set_coordinate_system(spine::skeleton::CoordinateSystem::YDown);
+    let mut walk = doc.get_animated_skin("default", Some("walk")).unwrap();
+    walk.set_flip(true, false);
+
+    let count = doc.get_bones_names().len();
+    let mut rows = vec![[0f32; 4]; 2 * count + 1];
+    let mut context = EvalContext::new();
+    assert_eq!(walk.write_bone_rows(0.3, &mut rows, &mut context), Some(2 * count));
+    for (i, m) in walk.bone_matrices(0.3).unwrap().iter().enumerate() {
+        let expected = [[m[0][0], m[1][0], m[2][0], 0.0], [m[0][1], m[1][1], m[2][1], 0.0]];
+        for (row, expected) in rows[2 * i..2 * i + 2].iter().zip(expected.iter()) {
+            for (a, b) in row.iter().zip(expected.iter()) {
+                assert!((a - b).abs() < 1e-3, "{:?} != {:?}", row, expected);
+            }
+        }
+    }
+
+    assert!(walk.write_bone_rows(0.3, &mut rows[..count], &mut context).is_none());
+    assert!(walk.write_bone_rows(walk.get_duration() + 1.0, &mut rows, &mut context).is_none());
+}
+
+#[test]
+fn collision_polygons() {
+    let src: &[u8] = include_bytes!("hitbox.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let anim = doc.get_animated_skin("default", Some("move")).unwrap();
+
+    let polygons = anim.collision_polygons(1.0, false).unwrap();
+    assert_eq!(polygons, vec![vec![[10.0, 10.0], [20.0, 10.0], [20.0, 20.0], [15.0, 15.0], [10.0, 20.0]]]);
+
+    let polygons = anim.collision_polygons(1.0, true).unwrap();
+    assert_eq!(polygons.len(), 2);
+    assert_eq!(polygons[0].len(), 4);
+    assert!(anim.collision_polygons(2.0, true).is_none());
+}
+
+#[test]
+fn animated_attachments() {
+    let src: &[u8] = include_bytes!("hitbox.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let hurt = doc.get_animated_skin("default", Some("hurt")).unwrap();
+    let names = |time| hurt.interpolate(time).unwrap().map(|s| s.attachment).collect::<Vec<_>>();
+    assert_eq!(names(0.2), ["body-hurt", "hitbox"]);
+    assert_eq!(names(0.5), ["body", "hitbox"]);
+
+    let info = doc.get_animation_info("hurt").unwrap();
+    let timeline = info.slot_timeline("body").unwrap().unwrap();
+    assert_eq!(timeline.interpolate_attachment_key(0.7), Some((1, Some("body"))));
+    assert_eq!(timeline.interpolate_attachment_key(-0.1), None);
+}
+
+#[test]
+fn dump_animation() {
+    use spine::skeleton::dump::{dump_animation, AnimationDump};
+
+    let src: &[u8] = include_bytes!("events.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    let step = doc.get_animated_skin("default", Some("step")).unwrap();
+
+    let dump = dump_animation(&step, 4.0);
+    assert_eq!(dump.bones, ["root", "body"]);
+    assert_eq!(dump.slots, ["body"]);
+    assert_eq!(dump.frames.len(), 4);
+    assert_eq!(dump.frames[3].time, 0.75);
+    assert!((dump.frames[2].bones[1][2].to_degrees() - 10.0).abs() < 1e-4);
+    assert_eq!(dump.frames[0].slots[0].0, Some("body".to_owned()));
+
+    let decoded: AnimationDump = serde_json::from_str(&serde_json::to_string(&dump).unwrap()).unwrap();
+    assert_eq!(decoded, dump);
+
+    // frames match the animation
+    for (frame, (_, srt)) in dump.frames[2].bones.iter().zip(step.bones_world_srts(0.5).unwrap()) {
+        assert_eq!(*frame, [srt.position[0], srt.position[1], srt.rotation, srt.scale[0], srt.scale[1]]);
+    }
+    assert!(::std::panic::catch_unwind(|| dump_animation(&step, 0.0)).is_err());
+}
+
+#[test]
+fn atlas_pages() {
+    use spine::atlas::{Atlas, Format, TextureFilter, TextureWrap};
+
+    let src: &[u8] = include_bytes!("example.atlas");
+    let names: Vec<_> = Atlas::from_reader(src).unwrap().map(|t| t.unwrap().name).collect();
+    assert_eq!(names, ["head", "torso", "eyes", "blink", "blink"]);
+    assert_eq!(Atlas::from_reader(src).unwrap().size, Some((128, 64)));
+
+    let pages: Vec<_> = Atlas::from_reader(src).unwrap().pages().map(|p| p.unwrap()).collect();
+    assert_eq!(pages.len(), 2);
+    assert_eq!(pages[0].file, "example.png");
+    assert_eq!(pages[0].textures.len(), 2);
+    assert_eq!(pages[0].size, Some((128, 64)));
+    assert_eq!(pages[1].size, None);
+    assert_eq!(pages[1].file, "example2.png");
+    assert_eq!(pages[1].format, Format::RGBA4444);
+    assert_eq!(pages[1].filter, (TextureFilter::Nearest, TextureFilter::Nearest));
+    assert_eq!(pages[1].repeat, (TextureWrap::Repeat, TextureWrap::Repeat));
+    assert_eq!(pages[0].repeat, (TextureWrap::ClampToEdge, TextureWrap::ClampToEdge));
+    assert_eq!("MipMapLinearLinear".parse::<TextureFilter>().ok(), Some(TextureFilter::MipMapLinearLinear));
+    assert!("Bilinear".parse::<TextureFilter>().is_err());
+    assert_eq!(pages[1].textures[0].name, "eyes");
+}
+
+#[test]
+fn atlas_41() {
+    use spine::atlas::{Atlas, Rotation};
+
+    let src: &[u8] = include_bytes!("example41.atlas");
+    let textures: Vec<_> = Atlas::from_reader(src).unwrap().map(|t| t.unwrap()).collect();
+    assert_eq!(textures.len(), 4);
+    assert_eq!(textures[0].xy, (2, 2));
+    assert_eq!(textures[0].orig, (64, 32));
+    assert_eq!(textures[0].rotate, Rotation::None);
+    assert_eq!(textures[1].rotate, Rotation::CW90);
+    assert_eq!(textures[1].size, (40, 60));
+    assert_eq!(textures[1].offset, (2, 4));
+    assert_eq!(textures[1].orig, (44, 66));
+    assert_eq!(textures[2].index, 1);
+    assert_eq!(textures[2].rotate, Rotation::CW270);
+    assert_eq!(textures[2].uvs((128, 64)), [[0.25, 0.0], [0.25, 0.25], [0.125, 0.25], [0.125, 0.0]]);
+    assert_eq!(textures[2].split, None);
+    assert_eq!(textures[3].split, Some([4, 4, 5, 5]));
+    assert_eq!(textures[3].pad, Some([2, 2, 3, 3]));
+
+    let pages: Vec<_> = Atlas::from_reader(src).unwrap().pages().map(|p| p.unwrap()).collect();
+    assert_eq!(pages.len(), 1);
+    assert_eq!(pages[0].size, Some((128, 64)));
+    assert_eq!(pages[0].format, spine::atlas::Format::RGBA8888);
+    assert_eq!(pages[0].filter, (spine::atlas::TextureFilter::Linear, spine::atlas::TextureFilter::Linear));
+    assert!(pages[0].pma);
+    assert_eq!(pages[0].scale, 0.5);
+}
+
+#[test]
+fn atlas_fields_order() {
+    use spine::atlas::Atlas;
+
+    let src: &[u8] = b"page.png\nfilter: Linear,Linear\npacker: custom\n\
+                       head\n  index: 2\n  size: 8, 4\n  xy: 3, 5\n  quality: high\n\
+                       torso\n  rotate: true\n";
+    let textures: Vec<_> = Atlas::from_reader(src).unwrap().map(|t| t.unwrap()).collect();
+    assert_eq!(textures.len(), 2);
+    assert_eq!((textures[0].xy, textures[0].size, textures[0].index), ((3, 5), (8, 4), 2));
+    assert_eq!((textures[0].orig, textures[0].offset), ((8, 4), (0, 0)));
+    assert_eq!(textures[0].rotate, spine::atlas::Rotation::None);
+    assert_eq!(textures[1].rotate, spine::atlas::Rotation::CW90);
+    assert_eq!((textures[1].xy, textures[1].index), ((0, 0), -1));
+}
+
+#[test]
+fn atlas_set() {
+    use spine::atlas::{Atlas, AtlasError, AtlasSet};
+
+    let mut set = AtlasSet::new();
+    let src: &[u8] = include_bytes!("example.atlas");
+    set.add(Atlas::from_reader(src).unwrap()).unwrap();
+
+    // "head" is in both atlases
+    let src: &[u8] = include_bytes!("example41.atlas");
+    match set.add(Atlas::from_reader(src).unwrap()) {
+        Err(AtlasError::DuplicateRegion(ref name)) => assert_eq!(name, "head"),
+        _ => panic!("regions must not conflict")
+    }
+    assert_eq!(set.pages().len(), 2);
+
+    let src: &[u8] = b"extra.png\nsize: 32,32\nbody\n  xy: 0, 0\n  size: 16, 32\n";
+    set.add(Atlas::from_reader(src).unwrap()).unwrap();
+    assert_eq!(set.pages().len(), 3);
+    assert_eq!(set.pages()[set.find("body").unwrap().0].file, "extra.png");
+    assert_eq!(set.find("blink").unwrap().1.xy, (32, 0));
+    assert_eq!(set.find_index("blink", 1).unwrap().0, 1);
+    assert!(set.find("legs").is_none());
+
+    let src: &[u8] = include_bytes!("events.json");
+    let mut doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    doc.bind_atlas_set(&set);
+    let anim = doc.get_animated_skin("default", Some("step")).unwrap();
+    let sprite = anim.interpolate(0.0).unwrap().next().unwrap();
+    assert_eq!(sprite.uvs, Some([[0.0, 0.0], [0.5, 0.0], [0.5, 1.0], [0.0, 1.0]]));
+}
+
+#[test]
+fn atlas_packer() {
+    use spine::atlas::{Atlas, AtlasError, Packer, Rotation};
+
+    let mut packer = Packer::new((64, 64));
+    packer.set_padding(2);
+    packer.set_rotation(true);
+    let page = packer.pack("packed.png", &[("a", (30, 10)), ("b", (10, 20)), ("c", (40, 40))]).unwrap();
+    let placements: Vec<_> = page.textures.iter().map(|t| (&*t.name, t.xy, t.rotate)).collect();
+    assert_eq!(placements, [("a", (0, 42), Rotation::None), ("b", (32, 42), Rotation::CW90),
+                            ("c", (0, 0), Rotation::None)]);
+
+    let mut file = Vec::new();
+    page.write_to(&mut file).unwrap();
+    let pages: Vec<_> = Atlas::from_reader(&file[..]).unwrap().pages().map(|p| p.unwrap()).collect();
+    assert_eq!(pages.len(), 1);
+    assert_eq!((&*pages[0].file, pages[0].size), ("packed.png", Some((64, 64))));
+    assert_eq!(pages[0].textures[1].rotate, Rotation::CW90);
+    assert_eq!(pages[0].textures[1].size, (10, 20));
+    assert_eq!(pages[0].textures[1].xy, (32, 42));
+
+    match packer.pack("packed.png", &[("a", (30, 10)), ("wide", (70, 10))]) {
+        Err(AtlasError::PageOverflow(ref name)) => assert_eq!(name, "wide"),
+        _ => panic!("the rectangle must not fit")
+    }
+}
+
+#[test]
+fn spine_bundle() {
+    use spine::bundle::{BundleError, SpineBundle};
+
+    let src: &[u8] = include_bytes!("events.json");
+    let atlas: &[u8] = b"\npage.png\nsize: 64,32\nbody\n  xy: 0, 0\n  size: 20, 40\n\n\
+                         armor.png\nsize: 32,64\narmor\n  xy: 0, 0\n  size: 30, 40\n";
+    let bundle = SpineBundle::load(src, atlas).unwrap();
+    assert_eq!(bundle.get_skeleton().get_images_names(), ["armor", "body"]);
+    assert_eq!(bundle.get_pages().len(), 2);
+
+    let anim = bundle.get_skeleton().get_animated_skin("armor", Some("step")).unwrap();
+    let sprite = anim.interpolate(0.0).unwrap().next().unwrap();
+    assert_eq!(sprite.page, Some(1));
+    assert_eq!(bundle.get_pages()[1].file, "armor.png");
+    assert_eq!(sprite.uvs, Some([[0.0, 0.0], [0.9375, 0.0], [0.9375, 0.625], [0.0, 0.625]]));
+
+    // the armor has no region
+    let atlas: &[u8] = b"page.png\nsize: 64,32\nbody\n  xy: 0, 0\n  size: 20, 40\n";
+    match SpineBundle::load(src, atlas) {
+        Err(BundleError::RegionNotFound(ref name)) => assert_eq!(name, "armor"),
+        _ => panic!("the armor region must be missing")
+    }
+}
+
+#[test]
+fn pages_for_skin() {
+    use spine::atlas::{Atlas, AtlasSet};
+
+    let src: &[u8] = include_bytes!("hitbox.json");
+    let doc = spine::skeleton::Skeleton::from_reader(BufReader::new(src)).unwrap();
+    assert_eq!(doc.get_skin_images_names("default").unwrap(), ["body", "body-hurt"]);
+    assert_eq!(doc.get_skin_images_names("goblin").unwrap(), ["body-hurt", "goblin"]);
+    assert!(doc.get_skin_images_names("orc").is_err());
+
+    let mut atlases = AtlasSet::new();
+    let src: &[u8] = b"body.png\nbody\n  size: 20, 40\n\ngoblin.png\ngoblin\n  size: 20, 40\n\n\
+                       hurt.png\nbody-hurt\n  size: 20, 40\n";
+    atlases.add(Atlas::from_reader(src).unwrap()).unwrap();
+    assert_eq!(doc.pages_for_skin(&atlases, "default").unwrap(), [0, 2]);
+    assert_eq!(doc.pages_for_skin(&atlases, "goblin").unwrap(), [1, 2]);
+}
+
+#[test]
+fn atlas_map() {
+    use spine::atlas::Atlas;
+
+    let src: &[u8] = include_bytes!("example.atlas");
+    let map = Atlas::from_reader(src).unwrap().into_map().unwrap();
+    assert_eq!(map.len(), 4);
+    assert_eq!(map["torso"][0].xy, (68, 2));
+    let blink: Vec<_> = map["blink"].iter().map(|t| (t.index, t.xy)).collect();
+    assert_eq!(blink, [(0, (32, 0)), (1, (16, 0))]);
+
+    let pages: Vec<_> = Atlas::from_reader(src).unwrap().pages().map(|p| p.unwrap()).collect();
+    let regions = pages[1].regions();
+    assert_eq!(regions.len(), 2);
+    assert_eq!(regions["blink"][0].index, 0);
+    assert!(!regions.contains_key("head"));
+}
@@ -0,0 +1,29 @@
+//! Computes bones world transforms with SIMD, `cargo test --features wide --test wide`
+
+#![cfg(feature = "wide")]
+
+extern crate spine;
+
+use spine::skeleton::Skeleton;
+use spine::skeleton::animation::EvalContext;
+
+#[test]
+fn simd_world_srts() {
+    let doc = Skeleton::from_file("tests/example.json").unwrap();
+    let anim = doc.get_animated_skin("default", Some("walk")).unwrap();
+    let mut rows = vec![[0f32; 4]; 2 * doc.get_bones_names().len()];
+    let mut context = EvalContext::new();
+
+    for &time in &[0.0, 0.3, 0.65] {
+        anim.write_bone_rows(time, &mut rows, &mut context).unwrap();
+        for (i, (_, srt)) in anim.bones_world_srts(time).unwrap().into_iter().enumerate() {
+            let m = srt.to_matrix3x2();
+            let expected = [[m[0][0], m[1][0], m[2][0]], [m[0][1], m[1][1], m[2][1]]];
+            for (row, expected) in rows[2 * i..2 * i + 2].iter().zip(expected.iter()) {
+                for (a, b) in row.iter().zip(expected.iter()) {
+                    assert!((a - b).abs() < 1e-3, "bone {} at {}: {:?} != {:?}", i, time, row, expected);
+                }
+            }
+        }
+    }
+}